@@ -0,0 +1,178 @@
+use anyhow::{anyhow, bail, Result};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::ai::SavedTranslation;
+use crate::app_setup;
+use crate::app_window::{self, AppWindow};
+use crate::config::SelectionConfig;
+use crate::notification::{self, TranslationResult};
+use crate::shortcut::ShortcutAction;
+use crate::state::AppState;
+use crate::{ai, clipboard_watch, selection, tray_menu};
+
+/// 执行快捷键或托盘菜单触发的操作
+pub fn run(app_handle: &AppHandle, action: ShortcutAction) {
+    println!("执行操作: {}", action.label());
+    match action {
+        ShortcutAction::ShowPopup => {
+            let app_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                show_popup(&app_handle).await;
+            });
+        }
+        ShortcutAction::OpenHistory => report(
+            app_handle,
+            action,
+            app_window::open(app_handle, AppWindow::History),
+        ),
+        ShortcutAction::ToggleLangPair => report(app_handle, action, toggle_lang_pair(app_handle)),
+        ShortcutAction::TranslateReplace => {
+            let app_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                report(&app_handle, action, replace_selection(&app_handle).await);
+            });
+        }
+        ShortcutAction::TranslateCopy => {
+            let app_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                report(&app_handle, action, translate_copy(&app_handle).await);
+            });
+        }
+    }
+}
+
+// 操作失败时打印错误，开启通知时同时通过系统通知显示
+fn report(app_handle: &AppHandle, action: ShortcutAction, result: Result<()>) {
+    if let Err(e) = result {
+        eprintln!("{}失败: {}", action.label(), e);
+        if app_handle.state::<AppState>().config().notification.enabled {
+            notification::show_error(app_handle, &format!("{}失败", action.label()), &e);
+        }
+    }
+}
+
+// 读取选中的文本后显示主窗口，并把文本交给前端翻译
+async fn show_popup(app_handle: &AppHandle) {
+    // 需要在主窗口获得焦点前读取，模拟复制时选中的文本还在原来的窗口中
+    let selection = app_handle.state::<AppState>().config().selection;
+    let content = selection::read(app_handle, &selection).await;
+    if !show_main_window(app_handle) {
+        return;
+    }
+    match content {
+        Ok(content) => {
+            println!("Selection content received:\n{}", content);
+            emit_content(app_handle, content);
+        }
+        Err(err) => {
+            eprintln!("Error getting selection content: {:?}", err);
+        }
+    }
+}
+
+/// 显示主窗口并把文本交给前端翻译
+pub fn show_in_popup(app_handle: &AppHandle, content: String) {
+    if show_main_window(app_handle) {
+        emit_content(app_handle, content);
+    }
+}
+
+/// 显示主窗口并显示完整的原文和译文，不重新翻译
+pub fn show_result(app_handle: &AppHandle, result: TranslationResult) {
+    if show_main_window(app_handle) {
+        app_handle
+            .emit("show-translation", result)
+            .unwrap_or_else(|e| eprintln!("Failed to emit event: {}", e));
+    }
+}
+
+fn emit_content(app_handle: &AppHandle, content: String) {
+    app_handle
+        .emit("clipboard-content", content)
+        .unwrap_or_else(|e| eprintln!("Failed to emit event: {}", e));
+}
+
+// 按配置的显示位置显示主窗口
+fn show_main_window(app_handle: &AppHandle) -> bool {
+    let Some(window) = app_handle.get_webview_window("main") else {
+        println!("Main window not found");
+        return false;
+    };
+    // 固定的窗口已显示时保持原来的位置
+    if !(app_setup::is_pinned(app_handle) && window.is_visible().unwrap_or(false)) {
+        if let Err(e) = app_setup::place_main_window(app_handle, &window) {
+            eprintln!("计算窗口位置失败: {}", e);
+        }
+    }
+    window
+        .show()
+        .unwrap_or_else(|e| eprintln!("显示窗口失败: {}", e));
+    window
+        .set_focus()
+        .unwrap_or_else(|e| eprintln!("窗口聚焦失败: {}", e));
+    true
+}
+
+// 翻译选中的文本并把译文写入剪贴板
+async fn translate_copy(app_handle: &AppHandle) -> Result<()> {
+    let config = app_handle.state::<AppState>().config();
+    let text = selection::read(app_handle, &config.selection).await?;
+    let translation = translate(app_handle, text.clone()).await?;
+    clipboard_watch::ignore(app_handle, &translation.text);
+    app_handle
+        .clipboard()
+        .write_text(translation.text.clone())?;
+    // 复制不会打开翻译窗口，通过通知显示译文
+    if config.notification.enabled {
+        let result = TranslationResult {
+            id: translation.id,
+            source_text: text,
+            translated_text: translation.text,
+        };
+        if let Err(e) = notification::show_result(app_handle, "译文已复制", result) {
+            eprintln!("显示通知失败: {}", e);
+        }
+    }
+    Ok(())
+}
+
+// 翻译选中的文本并粘贴替换，粘贴后恢复原有的剪贴板内容
+async fn replace_selection(app_handle: &AppHandle) -> Result<()> {
+    let config = app_handle.state::<AppState>().config();
+    // 替换时必须读取到真正选中的文本，总是模拟复制，不使用剪贴板中的旧内容；
+    // PRIMARY 选区在取消选中后仍保留上次选中的文本，也不使用
+    let selection = SelectionConfig {
+        use_primary: false,
+        ..config.selection.clone()
+    };
+    let text = selection::read_selected(app_handle, &selection, true)
+        .await
+        .ok_or_else(|| anyhow!("没有选中的文本"))?;
+    let translation = translate(app_handle, text).await?;
+    selection::paste(app_handle, &config.selection, translation.text).await
+}
+
+/// 按当前语言方向翻译
+pub async fn translate(app_handle: &AppHandle, text: String) -> Result<SavedTranslation> {
+    if text.trim().is_empty() {
+        bail!("没有选中的文本");
+    }
+    let state = app_handle.state::<AppState>();
+    let lang_pair = state.config().lang_pair;
+    let translation = ai::translate(&state, text, lang_pair.target, lang_pair.source, None).await?;
+    tray_menu::refresh(app_handle);
+    Ok(translation)
+}
+
+// 切换到下一个预设语言方向，并通知前端更新语言选择
+fn toggle_lang_pair(app_handle: &AppHandle) -> Result<()> {
+    let state = app_handle.state::<AppState>();
+    let mut config = state.config();
+    config.lang_pair = config.next_lang_pair();
+    let lang_pair = config.lang_pair;
+    state.update_config(config)?;
+    app_handle.emit("lang-pair-changed", lang_pair)?;
+    tray_menu::refresh(app_handle);
+    Ok(())
+}
@@ -1,16 +1,66 @@
-use anyhow::Result;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Result};
 use serde::{Deserialize, Serialize};
 
-use crate::config::AppConfig;
-use crate::config::{self, get_database, PlatformType};
+use crate::config::{self, AppConfig, PlatformType, ProviderProfile, ThinkingMode};
+use crate::database::{TranslationMeta, TranslationRecord};
+use crate::dictionary::DictionaryDefinition;
 use crate::lang::Lang;
+use crate::state::AppState;
+use dictionary::DictionaryEntry;
 
-#[derive(Serialize, Deserialize, Debug)]
+mod anthropic;
+mod deepl;
+pub mod dictionary;
+mod gemini;
+mod libre_translate;
+pub mod models;
+mod openai_compatible;
+mod rest;
+
+#[derive(Serialize, Deserialize, Debug, Default)]
 struct ChatRequest {
     model: String,
     messages: Vec<ChatMessage>,
     #[serde(skip_serializing_if = "Option::is_none")]
     stream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reasoning_effort: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<serde_json::Value>,
+    // 以下字段仅 Ollama 使用
+    #[serde(skip_serializing_if = "Option::is_none")]
+    think: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keep_alive: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    options: Option<ModelOptions>,
+}
+
+// Ollama 的模型参数
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+struct ModelOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    num_predict: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    num_ctx: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<i64>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -19,6 +69,22 @@ struct ChatMessage {
     content: String,
 }
 
+impl ChatMessage {
+    fn user(content: String) -> Self {
+        Self {
+            role: "user".to_string(),
+            content,
+        }
+    }
+
+    fn assistant(content: String) -> Self {
+        Self {
+            role: "assistant".to_string(),
+            content,
+        }
+    }
+}
+
 #[derive(Serialize, Debug)]
 
 struct MTranServerRequest {
@@ -32,56 +98,518 @@ struct MTranServerRequest {
 enum RequestPayload {
     Chat(ChatRequest),
     MTran(MTranServerRequest),
+    Anthropic(anthropic::MessagesRequest),
+    Gemini(gemini::GenerateContentRequest),
+    DeepL(deepl::TranslateRequest),
+    LibreTranslate(libre_translate::TranslateRequest),
+    Json(serde_json::Value),
+}
+
+// 解析后的翻译结果及服务端返回的 token 用量
+#[derive(Debug)]
+struct TranslationResponse {
+    text: String,
+    prompt_tokens: Option<i64>,
+    completion_tokens: Option<i64>,
+}
+
+/// 已保存到历史记录的翻译结果
+#[derive(Serialize, Debug, Clone)]
+pub struct SavedTranslation {
+    /// 翻译记录 ID，调整译文时使用
+    pub id: String,
+    pub text: String,
 }
 
-pub async fn translate(text: String, target_lang: Lang, source_lang: Lang) -> Result<String> {
-    let config = config::get_config()?;
-    let request_payload = build_request_payload(&text, target_lang, source_lang, &config)?;
-
-    let response =
-        send_translation_request(&config.api_url, &config.api_key, request_payload).await?;
-    let translated_text =
-        parse_translation_response(response, &config.platform, &config.model_name).await?;
-
-    let database = {
-        match get_database() {
-            Ok(db_arc) => match db_arc.lock() {
-                Ok(db_guard) => {
-                    if let Some(ref database) = *db_guard {
-                        database.clone()
-                    } else {
-                        return Err(anyhow::anyhow!("数据库未初始化"));
-                    }
+/// 对比翻译中单个翻译服务的结果
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CompareResult {
+    pub profile: String,
+    pub text: Option<String>,
+    pub error: Option<String>,
+    #[serde(flatten)]
+    pub meta: TranslationMeta,
+}
+
+/// 翻译上下文，用于消除短文本的歧义，只对大模型平台生效
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TranslationContext {
+    /// 待翻译文本之前的内容
+    pub before: Option<String>,
+    /// 待翻译文本之后的内容
+    pub after: Option<String>,
+    /// 领域提示，如 "software"、"medicine"
+    pub domain: Option<String>,
+    /// 作为示例的历史翻译（原文，译文），按时间先后排列
+    #[serde(skip)]
+    pub examples: Vec<(String, String)>,
+}
+
+impl TranslationContext {
+    // 追加到系统提示词后的上下文说明，没有上下文时返回 None
+    fn system_section(&self) -> Option<String> {
+        let non_empty = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        let mut lines = Vec::new();
+        if let Some(domain) = non_empty(&self.domain) {
+            lines.push(format!("Domain: {}", domain));
+        }
+        if let Some(before) = non_empty(&self.before) {
+            lines.push(format!("Text before: {}", before));
+        }
+        if let Some(after) = non_empty(&self.after) {
+            lines.push(format!("Text after: {}", after));
+        }
+        if lines.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "## Context\nUse the following context only to choose the right meaning. Do not translate or output it.\n{}",
+            lines.join("\n")
+        ))
+    }
+}
+
+/// 使用当前配置的翻译服务翻译文本并保存到历史记录
+///
+/// 配置了 `contextHistory` 时，本次启动后相同语言方向的最近几条翻译会作为示例发送给模型。
+pub async fn translate(
+    state: &AppState,
+    text: String,
+    target_lang: Lang,
+    source_lang: Lang,
+    context: Option<TranslationContext>,
+) -> Result<SavedTranslation> {
+    let config = state.config();
+    let mut context = context.unwrap_or_default();
+    if config.context_history > 0 && config.provider.platform.uses_prompt() {
+        context.examples = state
+            .database()
+            .await?
+            .recent_translations(
+                state.started_at(),
+                source_lang.into(),
+                target_lang.into(),
+                config.context_history,
+            )
+            .await?
+            .into_iter()
+            .map(|record| (record.source_text, record.translated_text))
+            .collect();
+    }
+
+    let started_at = Instant::now();
+    let translation = request_translation(
+        &config,
+        &config.provider,
+        &text,
+        target_lang,
+        source_lang,
+        &context,
+    )
+    .await?;
+    let meta = translation_meta(
+        &config,
+        &config.provider,
+        started_at.elapsed(),
+        Some(&translation),
+    );
+
+    let id = state
+        .database()
+        .await?
+        .save_translation(
+            &text,
+            &translation.text,
+            source_lang.into(),
+            target_lang.into(),
+            &meta,
+        )
+        .await?;
+
+    Ok(SavedTranslation {
+        id,
+        text: translation.text,
+    })
+}
+
+/// 将同一段文本同时发送给多个翻译服务，结果顺序与 `profiles` 一致
+///
+/// 单个服务失败不会影响其他服务，错误信息记录在对应结果的 `error` 中。对比结果不会写入历史记录。
+pub async fn translate_compare(
+    config: &AppConfig,
+    profiles: Vec<ProviderProfile>,
+    text: &str,
+    target_lang: Lang,
+    source_lang: Lang,
+) -> Vec<CompareResult> {
+    let tasks: Vec<_> = profiles
+        .into_iter()
+        .map(|profile| {
+            let config = config.clone();
+            let text = text.to_string();
+            let name = profile.name.clone();
+            let handle = tokio::spawn(async move {
+                let started_at = Instant::now();
+                let result = request_translation(
+                    &config,
+                    &profile,
+                    &text,
+                    target_lang,
+                    source_lang,
+                    &TranslationContext::default(),
+                )
+                .await;
+                let elapsed = started_at.elapsed();
+                match result {
+                    Ok(translation) => CompareResult {
+                        profile: profile.name.clone(),
+                        meta: translation_meta(&config, &profile, elapsed, Some(&translation)),
+                        text: Some(translation.text),
+                        error: None,
+                    },
+                    Err(e) => CompareResult {
+                        profile: profile.name.clone(),
+                        text: None,
+                        error: Some(e.to_string()),
+                        meta: translation_meta(&config, &profile, elapsed, None),
+                    },
                 }
-                Err(_) => return Err(anyhow::anyhow!("数据库锁定失败")),
-            },
-            Err(e) => return Err(anyhow::anyhow!("获取数据库连接失败: {:?}", e)),
+            });
+            (name, handle)
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(tasks.len());
+    for (name, handle) in tasks {
+        results.push(handle.await.unwrap_or_else(|e| CompareResult {
+            profile: name,
+            text: None,
+            error: Some(format!("翻译任务异常退出: {}", e)),
+            meta: TranslationMeta::default(),
+        }));
+    }
+    results
+}
+
+/// 词典模式的结果，`entry` 和 `definitions` 都为空时表示按普通翻译处理
+#[derive(Serialize, Debug)]
+pub struct DictionaryResult {
+    /// 翻译记录 ID，使用离线词典的释义时不保存记录，为空
+    pub id: Option<String>,
+    pub translation: String,
+    pub entry: Option<DictionaryEntry>,
+    /// 离线词典中的释义，有结果时不再请求翻译服务
+    pub definitions: Vec<DictionaryDefinition>,
+}
+
+/// 词典模式：短文本返回多个候选译文、词性、发音和例句，其他文本按普通翻译处理
+///
+/// 单个单词优先查询离线词典；模型返回的内容无法解析为词典结构时，改用普通翻译。
+pub async fn translate_dictionary(
+    state: &AppState,
+    text: String,
+    target_lang: Lang,
+    source_lang: Lang,
+) -> Result<DictionaryResult> {
+    if crate::dictionary::is_single_word(&text) {
+        let definitions = state.database().await?.lookup_word(&text).await?;
+        if let Some(first) = definitions.first() {
+            let translation = first
+                .definition
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string();
+            return Ok(DictionaryResult {
+                id: None,
+                translation,
+                entry: None,
+                definitions,
+            });
+        }
+    }
+
+    let config = state.config();
+    let profile = &config.provider;
+    if !dictionary::is_short_text(&text) || !profile.platform.uses_prompt() {
+        let saved = translate(state, text, target_lang, source_lang, None).await?;
+        return Ok(DictionaryResult {
+            id: Some(saved.id),
+            translation: saved.text,
+            entry: None,
+            definitions: Vec::new(),
+        });
+    }
+
+    let started_at = Instant::now();
+    let mut payload = build_chat_payload(
+        profile,
+        dictionary::system_prompt(source_lang, target_lang),
+        vec![ChatMessage::user(text.trim().to_string())],
+    )?;
+    request_json_output(&mut payload, &profile.platform);
+    let response = send_translation_request(profile, payload).await?;
+    let response = parse_translation_response(response, profile).await?;
+    let entry = match dictionary::parse_entry(&strip_think_blocks(&response.text)) {
+        Ok(entry) => entry,
+        Err(e) => {
+            eprintln!("词典模式解析失败，改用普通翻译: {}", e);
+            let saved = translate(state, text, target_lang, source_lang, None).await?;
+            return Ok(DictionaryResult {
+                id: Some(saved.id),
+                translation: saved.text,
+                entry: None,
+                definitions: Vec::new(),
+            });
         }
     };
 
-    database
+    let translation = entry.primary_translation().unwrap_or_default().to_string();
+    let meta = translation_meta(&config, profile, started_at.elapsed(), Some(&response));
+    let id = state
+        .database()
+        .await?
         .save_translation(
-            &text,
-            &translated_text,
+            text.trim(),
+            &translation,
             source_lang.into(),
             target_lang.into(),
+            &meta,
         )
         .await?;
 
-    Ok(translated_text)
+    Ok(DictionaryResult {
+        id: Some(id),
+        translation,
+        entry: Some(entry),
+        definitions: Vec::new(),
+    })
+}
+
+// 要求支持 JSON 输出模式的平台只返回 JSON，其他平台依靠提示词约束
+fn request_json_output(payload: &mut RequestPayload, platform: &PlatformType) {
+    if let RequestPayload::Chat(request) = payload {
+        if platform == &PlatformType::OLLama {
+            request.format = Some("json".to_string());
+        } else {
+            request.response_format = Some(serde_json::json!({ "type": "json_object" }));
+        }
+    }
+}
+
+/// 按调整要求修改已有的翻译，如“更正式”、“更简短”，修订结果保存为原始记录的子记录
+///
+/// 对话由原始翻译及其已有的修订重建，重启应用后仍可继续调整。`record_id` 为当前显示的译文对应的记录，
+/// 传入修订记录时继续其原始记录的对话。
+pub async fn refine_translation(
+    state: &AppState,
+    record_id: &str,
+    instruction: &str,
+) -> Result<TranslationRecord> {
+    let instruction = instruction.trim();
+    if instruction.is_empty() {
+        bail!("调整要求不能为空");
+    }
+    let config = state.config();
+    let profile = &config.provider;
+    if !profile.platform.uses_prompt() {
+        bail!("{} 不支持调整译文", profile.platform.as_str());
+    }
+
+    let database = state.database().await?;
+    let record = database
+        .get_translation(record_id)
+        .await?
+        .ok_or_else(|| anyhow!("未找到翻译记录: {}", record_id))?;
+    let original = match &record.parent_id {
+        Some(parent_id) => database
+            .get_translation(parent_id)
+            .await?
+            .ok_or_else(|| anyhow!("未找到原始翻译记录: {}", parent_id))?,
+        None => record,
+    };
+    let revisions = database.get_revisions(&original.id).await?;
+
+    let source_lang = Lang::from_code(&original.source_lang).unwrap_or(Lang::Auto);
+    let target_lang = Lang::from_code(&original.target_lang)
+        .ok_or_else(|| anyhow!("无法识别的目标语言: {}", original.target_lang))?;
+    let messages = refine_messages(&config, &original, &revisions, instruction, target_lang);
+
+    let started_at = Instant::now();
+    let payload = build_chat_payload(
+        profile,
+        render_system_prompt(&config, source_lang),
+        messages,
+    )?;
+    let response = send_translation_request(profile, payload).await?;
+    let mut translation = parse_translation_response(response, profile).await?;
+    translation.text = strip_think_blocks(&translation.text);
+    let meta = translation_meta(&config, profile, started_at.elapsed(), Some(&translation));
+
+    let id = database
+        .save_revision(&original, instruction, &translation.text, &meta)
+        .await?;
+    database
+        .get_translation(&id)
+        .await?
+        .ok_or_else(|| anyhow!("未找到保存的修订记录: {}", id))
+}
+
+// 原始翻译和每次修订各为一轮对话，最后一条消息是本次的调整要求
+fn refine_messages(
+    config: &AppConfig,
+    original: &TranslationRecord,
+    revisions: &[TranslationRecord],
+    instruction: &str,
+    target_lang: Lang,
+) -> Vec<ChatMessage> {
+    let mut messages = vec![
+        ChatMessage::user(render_prompt(config, &original.source_text, target_lang)),
+        ChatMessage::assistant(original.translated_text.clone()),
+    ];
+    for revision in revisions {
+        let instruction = revision.instruction.as_deref().unwrap_or_default();
+        messages.push(ChatMessage::user(refine_prompt(instruction)));
+        messages.push(ChatMessage::assistant(revision.translated_text.clone()));
+    }
+    messages.push(ChatMessage::user(refine_prompt(instruction)));
+    messages
+}
+
+fn refine_prompt(instruction: &str) -> String {
+    format!(
+        "Revise the translation: {}\n\nOutput only the revised translation.",
+        instruction
+    )
+}
+
+// 向指定的翻译服务发送请求并解析结果
+async fn request_translation(
+    config: &AppConfig,
+    profile: &ProviderProfile,
+    text: &str,
+    target_lang: Lang,
+    source_lang: Lang,
+    context: &TranslationContext,
+) -> Result<TranslationResponse> {
+    let response = if profile.platform == PlatformType::RestApi {
+        rest::build_request(profile, text, target_lang, source_lang)?
+            .send()
+            .await?
+    } else {
+        let request_payload =
+            build_request_payload(text, target_lang, source_lang, config, profile, context)?;
+        send_translation_request(profile, request_payload).await?
+    };
+    let mut translation = parse_translation_response(response, profile).await?;
+
+    // 推理模型可能在译文前输出思考过程
+    if profile.platform.uses_prompt() {
+        translation.text = strip_think_blocks(&translation.text);
+    }
+    Ok(translation)
+}
+
+/// 去除所有 `<think>...</think>` 块
+///
+/// 部分接口会省略开头的 `<think>` 标签，此时去除第一个 `</think>` 之前的全部内容；
+/// 未闭合的 `<think>`（输出被截断）会去除其后的全部内容。
+fn strip_think_blocks(text: &str) -> String {
+    const OPEN: &str = "<think>";
+    const CLOSE: &str = "</think>";
+
+    let mut rest = text;
+    if let Some(close) = rest.find(CLOSE) {
+        if !rest[..close].contains(OPEN) {
+            rest = &rest[close + CLOSE.len()..];
+        }
+    }
+
+    let mut output = String::with_capacity(rest.len());
+    while let Some(open) = rest.find(OPEN) {
+        output.push_str(&rest[..open]);
+        match rest[open..].find(CLOSE) {
+            Some(close) => rest = &rest[open + close + CLOSE.len()..],
+            None => {
+                rest = "";
+                break;
+            }
+        }
+    }
+    output.push_str(rest);
+    output.trim().to_string()
+}
+
+// 生成保存到历史记录中的请求信息
+fn translation_meta(
+    config: &AppConfig,
+    profile: &ProviderProfile,
+    elapsed: Duration,
+    translation: Option<&TranslationResponse>,
+) -> TranslationMeta {
+    // 机器翻译引擎不使用模型和提示词，不记录这两项
+    let uses_prompt = profile.platform.uses_prompt();
+    TranslationMeta {
+        platform: Some(profile.platform.as_str().to_string()),
+        model_name: uses_prompt.then(|| profile.model_name.clone()),
+        prompt_hash: uses_prompt.then(|| prompt_hash(config)),
+        duration_ms: Some(elapsed.as_millis() as i64),
+        prompt_tokens: translation.and_then(|t| t.prompt_tokens),
+        completion_tokens: translation.and_then(|t| t.completion_tokens),
+    }
+}
+
+/// 计算系统提示词和提示词模板的哈希，用于区分产生翻译的提示词版本
+///
+/// 使用 FNV-1a 算法，保证不同版本的程序得到相同的结果。
+fn prompt_hash(config: &AppConfig) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in config
+        .system_prompt
+        .bytes()
+        .chain(std::iter::once(0))
+        .chain(config.prompt.bytes())
+    {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
 }
 
 async fn send_translation_request(
-    api_url: &str,
-    api_key: &str,
+    profile: &ProviderProfile,
     request_payload: RequestPayload,
 ) -> Result<reqwest::Response> {
-    let response = config::REQUEST_CLIENT
-        .post(api_url)
-        .header("Authorization", format!("Bearer {}", api_key))
-        .json(&request_payload)
-        .send()
-        .await?;
+    let client = &config::REQUEST_CLIENT;
+    let request = match profile.platform {
+        PlatformType::Anthropic => {
+            anthropic::authorize(client.post(&profile.api_url), &profile.api_key)
+        }
+        PlatformType::Gemini => gemini::authorize(
+            client.post(gemini::endpoint(&profile.api_url, &profile.model_name)),
+            &profile.api_key,
+        ),
+        PlatformType::DeepL => {
+            deepl::authorize(client.post(deepl::endpoint(profile)), &profile.api_key)
+        }
+        // API Key 放在请求体中
+        PlatformType::LibreTranslate => client.post(&profile.api_url),
+        PlatformType::OpenAICompatible => openai_compatible::authorize(
+            client.post(&profile.api_url),
+            profile,
+            &profile.openai_compatible.clone().unwrap_or_default(),
+        ),
+        _ => client
+            .post(&profile.api_url)
+            .header("Authorization", format!("Bearer {}", profile.api_key)),
+    };
+    let response = request.json(&request_payload).send().await?;
     Ok(response)
 }
 
@@ -90,81 +618,306 @@ fn build_request_payload(
     target_lang: Lang,
     source_lang: Lang,
     config: &AppConfig,
+    profile: &ProviderProfile,
+    context: &TranslationContext,
 ) -> Result<RequestPayload> {
-    let system_prompt = config
+    match profile.platform {
+        PlatformType::MTranServer => {
+            return Ok(RequestPayload::MTran(MTranServerRequest {
+                from: source_lang.into(),
+                to: target_lang.into(),
+                text: text.to_string(),
+            }))
+        }
+        PlatformType::DeepL => {
+            return Ok(RequestPayload::DeepL(deepl::build_request(
+                profile,
+                text,
+                target_lang,
+                source_lang,
+            )?))
+        }
+        PlatformType::LibreTranslate => {
+            return Ok(RequestPayload::LibreTranslate(
+                libre_translate::build_request(profile, text, target_lang, source_lang)?,
+            ))
+        }
+        PlatformType::RestApi => bail!("通用 REST 接口的请求由模板生成"),
+        _ => {}
+    }
+
+    let mut system_prompt = render_system_prompt(config, source_lang);
+    if let Some(section) = context.system_section() {
+        system_prompt = format!("{}\n\n{}", system_prompt.trim_end(), section);
+    }
+
+    // 历史翻译作为多轮对话示例放在本次请求之前
+    let mut messages = Vec::with_capacity(context.examples.len() * 2 + 1);
+    for (source_text, translated_text) in &context.examples {
+        messages.push(ChatMessage::user(render_prompt(
+            config,
+            source_text,
+            target_lang,
+        )));
+        messages.push(ChatMessage::assistant(translated_text.clone()));
+    }
+    messages.push(ChatMessage::user(render_prompt(config, text, target_lang)));
+
+    build_chat_payload(profile, system_prompt, messages)
+}
+
+fn render_system_prompt(config: &AppConfig, source_lang: Lang) -> String {
+    config
         .system_prompt
-        .replace("{{to}}", source_lang.to_full_name());
-    let mut prompt = config
+        .replace("{{to}}", source_lang.to_full_name())
+}
+
+fn render_prompt(config: &AppConfig, text: &str, target_lang: Lang) -> String {
+    config
         .prompt
         .replace("{{text}}", text)
-        .replace("{{to}}", target_lang.to_full_name());
+        .replace("{{to}}", target_lang.to_full_name())
+}
 
-    match config.platform {
-        PlatformType::OLLama => {
-            if config.model_name.contains("qwen3") {
-                prompt.push_str(" /no_think");
-            }
-            Ok(RequestPayload::Chat(ChatRequest {
-                model: config.model_name.clone(),
-                messages: vec![
-                    ChatMessage {
-                        role: "system".to_string(),
-                        content: system_prompt,
-                    },
-                    ChatMessage {
-                        role: "user".to_string(),
-                        content: prompt,
-                    },
-                ],
-                stream: Some(false),
-            }))
+// 按平台构建大模型的对话请求，最后一条消息为本次的用户输入
+fn build_chat_payload(
+    profile: &ProviderProfile,
+    system_prompt: String,
+    mut messages: Vec<ChatMessage>,
+) -> Result<RequestPayload> {
+    if profile.thinking_mode() == ThinkingMode::NoThinkSuffix {
+        if let Some(last) = messages.last_mut() {
+            last.content.push_str(" /no_think");
+        }
+    }
+
+    match profile.platform {
+        PlatformType::Anthropic => Ok(RequestPayload::Anthropic(anthropic::build_request(
+            &profile.model_name,
+            system_prompt,
+            messages,
+            profile.stream,
+            &profile.sampling,
+        ))),
+        PlatformType::Gemini => Ok(RequestPayload::Gemini(gemini::build_request(
+            system_prompt,
+            messages,
+            &profile.sampling,
+            profile.thinking_mode() == ThinkingMode::Disabled,
+        ))),
+        PlatformType::OpenAICompatible => {
+            let options = profile.openai_compatible.clone().unwrap_or_default();
+            Ok(RequestPayload::Json(openai_compatible::build_request(
+                chat_request(profile, system_prompt, messages),
+                &options,
+            )?))
+        }
+        _ => Ok(RequestPayload::Chat(chat_request(
+            profile,
+            system_prompt,
+            messages,
+        ))),
+    }
+}
+
+// OpenAI 格式的对话请求，Ollama 的 /api/chat 也使用相同结构，但采样参数放在 options 中
+fn chat_request(
+    profile: &ProviderProfile,
+    system_prompt: String,
+    messages: Vec<ChatMessage>,
+) -> ChatRequest {
+    let sampling = &profile.sampling;
+    let system = ChatMessage {
+        role: "system".to_string(),
+        content: system_prompt,
+    };
+    let mut request = ChatRequest {
+        model: profile.model_name.clone(),
+        messages: std::iter::once(system).chain(messages).collect(),
+        stream: Some(false),
+        ..Default::default()
+    };
+
+    if profile.platform == PlatformType::OLLama {
+        let ollama = profile.ollama.clone().unwrap_or_default();
+        if profile.thinking_mode() == ThinkingMode::Disabled {
+            request.think = Some(false);
         }
-        PlatformType::MTranServer => Ok(RequestPayload::MTran(MTranServerRequest {
-            from: source_lang.into(),
-            to: target_lang.into(),
-            text: text.to_string(),
-        })),
-        _ => Ok(RequestPayload::Chat(ChatRequest {
-            model: config.model_name.clone(),
-            messages: vec![
-                ChatMessage {
-                    role: "system".to_string(),
-                    content: system_prompt,
-                },
-                ChatMessage {
-                    role: "user".to_string(),
-                    content: prompt,
-                },
-            ],
-            stream: Some(false),
-        })),
+        request.keep_alive = ollama.keep_alive;
+        let options = ModelOptions {
+            temperature: sampling.temperature,
+            top_p: sampling.top_p,
+            num_predict: ollama.num_predict.or(sampling.max_tokens),
+            num_ctx: ollama.num_ctx,
+            seed: sampling.seed,
+        };
+        if options != ModelOptions::default() {
+            request.options = Some(options);
+        }
+    } else {
+        request.temperature = sampling.temperature;
+        request.top_p = sampling.top_p;
+        request.max_tokens = sampling.max_tokens;
+        request.seed = sampling.seed;
+        // 只发送配置中设置的值，许多模型和兼容服务不接受该字段
+        request.reasoning_effort = sampling.reasoning_effort.clone();
     }
+    request
+}
+
+/// 预加载 Ollama 模型，使启动后的第一次翻译不必等待模型加载
+///
+/// 发送不含消息的对话请求，Ollama 只加载模型而不生成内容。其他平台不做任何处理。
+pub async fn warm_up(profile: &ProviderProfile) -> Result<()> {
+    let warm_up = profile.ollama.as_ref().is_none_or(|ollama| ollama.warm_up);
+    if profile.platform != PlatformType::OLLama || !warm_up {
+        return Ok(());
+    }
+
+    // 参数需要与翻译请求一致，否则 Ollama 会重新加载模型
+    let mut request = chat_request(profile, String::new(), Vec::new());
+    request.messages.clear();
+    let response = config::REQUEST_CLIENT
+        .post(&profile.api_url)
+        .json(&request)
+        .send()
+        .await?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        bail!("预加载模型失败 ({}): {}", status, error_message(&body));
+    }
+    Ok(())
 }
 
 async fn parse_translation_response(
     response: reqwest::Response,
-    platform: &PlatformType,
-    model_name: &str,
-) -> Result<String> {
+    profile: &ProviderProfile,
+) -> Result<TranslationResponse> {
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        let message = match profile.platform {
+            PlatformType::DeepL => deepl::error_message(status, &body),
+            _ => error_message(&body),
+        };
+        return Err(anyhow::anyhow!(
+            "{} 请求失败 ({}): {}",
+            profile.platform.as_str(),
+            status,
+            message
+        ));
+    }
+
+    if profile.platform == PlatformType::Anthropic && profile.stream {
+        return anthropic::read_event_stream(response).await;
+    }
+
     let json: serde_json::Value = response.json().await?;
+    if profile.platform == PlatformType::RestApi {
+        return rest::parse_response(&json, profile);
+    }
+    parse_translation_json(&json, &profile.platform)
+}
 
-    let content = match platform {
-        PlatformType::OLLama => json["message"]["content"]
-            .as_str()
-            .ok_or_else(|| anyhow::anyhow!("Failed to parse OLLama response"))?,
-        PlatformType::MTranServer => json["result"]
-            .as_str()
-            .ok_or_else(|| anyhow::anyhow!("Failed to parse MTranServer response"))?,
-        _ => json["choices"][0]["message"]["content"]
-            .as_str()
-            .ok_or_else(|| anyhow::anyhow!("Failed to parse ChatGPT/DeepSeek response"))?,
+// 从错误响应中提取错误信息，兼容各平台的常见格式
+fn error_message(body: &str) -> String {
+    let json: serde_json::Value = match serde_json::from_str(body) {
+        Ok(json) => json,
+        Err(_) => return body.trim().chars().take(200).collect(),
     };
+    json["error"]["message"]
+        .as_str()
+        .or_else(|| json["error"].as_str())
+        .or_else(|| json["message"].as_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| json.to_string())
+}
 
-    // 处理特殊模型的响应清理
-    if platform == &PlatformType::OLLama && model_name.contains("qwen3") {
-        Ok(content.replace("<think>\n\n</think>\n\n", ""))
-    } else {
-        Ok(content.to_string())
+fn parse_translation_json(
+    json: &serde_json::Value,
+    platform: &PlatformType,
+) -> Result<TranslationResponse> {
+    let (content, prompt_tokens, completion_tokens) = match platform {
+        PlatformType::OLLama => (
+            json["message"]["content"]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("Failed to parse OLLama response"))?,
+            json["prompt_eval_count"].as_i64(),
+            json["eval_count"].as_i64(),
+        ),
+        PlatformType::Anthropic => return anthropic::parse_response(json),
+        PlatformType::Gemini => return gemini::parse_response(json),
+        PlatformType::DeepL => return deepl::parse_response(json),
+        PlatformType::LibreTranslate => return libre_translate::parse_response(json),
+        PlatformType::MTranServer => (
+            json["result"]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("Failed to parse MTranServer response"))?,
+            None,
+            None,
+        ),
+        _ => (
+            json["choices"][0]["message"]["content"]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("Failed to parse ChatGPT/DeepSeek response"))?,
+            json["usage"]["prompt_tokens"].as_i64(),
+            json["usage"]["completion_tokens"].as_i64(),
+        ),
+    };
+
+    Ok(TranslationResponse {
+        text: content.to_string(),
+        prompt_tokens,
+        completion_tokens,
+    })
+}
+
+// 各翻译服务测试共用的 mock 服务器和请求
+#[cfg(test)]
+mod test_support {
+    use super::*;
+
+    /// 启动 mock 服务器，返回请求地址为服务器上 `path` 的翻译服务配置
+    pub async fn mock_provider(
+        platform: PlatformType,
+        path: &str,
+    ) -> (mockito::ServerGuard, ProviderProfile) {
+        let server = mockito::Server::new_async().await;
+        let profile = ProviderProfile {
+            name: "test".to_string(),
+            api_key: "test-key".to_string(),
+            api_url: format!("{}{}", server.url(), path),
+            platform,
+            ..Default::default()
+        };
+        (server, profile)
+    }
+
+    /// 使用默认配置把“你好”翻译为英文
+    pub async fn translate(profile: &ProviderProfile) -> Result<TranslationResponse> {
+        request_translation(
+            &AppConfig::default(),
+            profile,
+            "你好",
+            Lang::En,
+            Lang::Zh,
+            &Default::default(),
+        )
+        .await
+    }
+
+    /// 服务以指定的状态码和响应体返回错误时得到的错误信息
+    pub async fn error_message(platform: PlatformType, status: usize, body: &str) -> String {
+        let (mut server, profile) = mock_provider(platform, "").await;
+        server
+            .mock("POST", mockito::Matcher::Any)
+            .with_status(status)
+            .with_header("content-type", "application/json")
+            .with_body(body)
+            .create_async()
+            .await;
+        translate(&profile).await.unwrap_err().to_string()
     }
 }
 
@@ -175,13 +928,389 @@ mod tests {
 
     #[tokio::test]
     async fn test_translate() {
+        let (mut server, profile) =
+            test_support::mock_provider(PlatformType::OLLama, "/api/chat").await;
+        let mock = server
+            .mock("POST", "/api/chat")
+            .match_body(mockito::Matcher::PartialJson(
+                serde_json::json!({ "think": false }),
+            ))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"message":{"role":"assistant","content":"<think>\n\n</think>\n\n这些短语是日常生活中非常常用的基本表达。"},"prompt_eval_count":42,"eval_count":12}"#,
+            )
+            .create_async()
+            .await;
+
+        let dir = std::env::temp_dir().join(format!("tauri_yi_translate_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db_path = dir.join("history.db");
+        std::fs::write(&db_path, b"").unwrap();
+        let config = AppConfig {
+            provider: profile,
+            ..Default::default()
+        };
+        let state = AppState::new(config, dir.join("config.json"), db_path);
         let result = translate(
-            "これらのフレーズは、日常で非常によく使われる基本的なものです。/no_think".to_string(),
+            &state,
+            "これらのフレーズは、日常で非常によく使われる基本的なものです。".to_string(),
             Lang::Zh,
             Lang::Ja,
+            None,
+        )
+        .await
+        .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(result.text, "这些短语是日常生活中非常常用的基本表达。");
+        let record = state
+            .database()
+            .await
+            .unwrap()
+            .get_translation(&result.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(record.translated_text, result.text);
+        assert_eq!(record.meta.prompt_tokens, Some(42));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_translation_usage() {
+        let ollama = serde_json::json!({
+            "message": { "role": "assistant", "content": "<think>\n\n</think>\n\n你好" },
+            "prompt_eval_count": 42,
+            "eval_count": 7
+        });
+        let result = parse_translation_json(&ollama, &PlatformType::OLLama).unwrap();
+        assert_eq!(strip_think_blocks(&result.text), "你好");
+        assert_eq!(result.prompt_tokens, Some(42));
+        assert_eq!(result.completion_tokens, Some(7));
+
+        let openai = serde_json::json!({
+            "choices": [{ "message": { "role": "assistant", "content": "Hello" } }],
+            "usage": { "prompt_tokens": 30, "completion_tokens": 2, "total_tokens": 32 }
+        });
+        let result = parse_translation_json(&openai, &PlatformType::DeepSeek).unwrap();
+        assert_eq!(result.text, "Hello");
+        assert_eq!(result.prompt_tokens, Some(30));
+        assert_eq!(result.completion_tokens, Some(2));
+
+        let mtran = serde_json::json!({ "result": "Hello" });
+        let result = parse_translation_json(&mtran, &PlatformType::MTranServer).unwrap();
+        assert_eq!(result.prompt_tokens, None);
+    }
+
+    #[test]
+    fn test_prompt_hash_changes_with_prompt() {
+        let config = AppConfig::default();
+        let mut changed = config.clone();
+        changed.prompt.push('!');
+        assert_eq!(prompt_hash(&config), prompt_hash(&config.clone()));
+        assert_ne!(prompt_hash(&config), prompt_hash(&changed));
+    }
+
+    #[tokio::test]
+    async fn test_translate_compare_keeps_order_and_errors() {
+        let mut server = mockito::Server::new_async().await;
+        let ok = server
+            .mock("POST", "/translate")
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"result":"Hello"}"#)
+            .create_async()
+            .await;
+        let failing = server
+            .mock("POST", "/broken")
+            .with_status(500)
+            .with_body("internal error")
+            .create_async()
+            .await;
+
+        let profile = |name: &str, path: &str| ProviderProfile {
+            name: name.to_string(),
+            api_key: String::new(),
+            api_url: format!("{}{}", server.url(), path),
+            platform: PlatformType::MTranServer,
+            model_name: String::new(),
+            ..Default::default()
+        };
+        let results = translate_compare(
+            &AppConfig::default(),
+            vec![profile("mtran", "/translate"), profile("broken", "/broken")],
+            "你好",
+            Lang::En,
+            Lang::Zh,
         )
         .await;
-        assert!(result.is_ok());
-        println!("Translation: {:?}", result.unwrap());
+
+        ok.assert_async().await;
+        failing.assert_async().await;
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].profile, "mtran");
+        assert_eq!(results[0].text.as_deref(), Some("Hello"));
+        assert_eq!(results[0].meta.platform.as_deref(), Some("MTranServer"));
+        assert_eq!(results[1].profile, "broken");
+        assert!(results[1].text.is_none());
+        assert!(results[1].error.is_some());
+    }
+
+    #[test]
+    fn test_strip_think_blocks() {
+        assert_eq!(strip_think_blocks("<think>\n\n</think>\n\n你好"), "你好");
+        assert_eq!(
+            strip_think_blocks("<think>\nThe user wants...\n</think>\nHello <think>x</think>world"),
+            "Hello world"
+        );
+        assert_eq!(
+            strip_think_blocks("reasoning without tag</think>\nHello"),
+            "Hello"
+        );
+        assert_eq!(strip_think_blocks("Hello<think>truncated"), "Hello");
+        assert_eq!(strip_think_blocks("a < b"), "a < b");
+    }
+
+    #[test]
+    fn test_chat_request_sampling_by_platform() {
+        let mut profile = ProviderProfile {
+            sampling: crate::config::SamplingOptions {
+                temperature: Some(0.3),
+                max_tokens: Some(256),
+                seed: Some(7),
+                ..Default::default()
+            },
+            thinking: Some(ThinkingMode::Disabled),
+            ..Default::default()
+        };
+
+        let ollama =
+            serde_json::to_value(chat_request(&profile, String::new(), Vec::new())).unwrap();
+        assert_eq!(ollama["think"], false);
+        assert_eq!(ollama["options"]["num_predict"], 256);
+        assert_eq!(ollama["options"]["seed"], 7);
+        assert!(ollama.get("temperature").is_none());
+
+        profile.platform = PlatformType::ChatGPT;
+        let openai =
+            serde_json::to_value(chat_request(&profile, String::new(), Vec::new())).unwrap();
+        assert_eq!(openai["temperature"], 0.3);
+        assert_eq!(openai["max_tokens"], 256);
+        assert!(openai.get("reasoning_effort").is_none());
+        assert!(openai.get("think").is_none());
+        assert!(openai.get("options").is_none());
+
+        profile.sampling.reasoning_effort = Some("low".to_string());
+        let openai =
+            serde_json::to_value(chat_request(&profile, String::new(), Vec::new())).unwrap();
+        assert_eq!(openai["reasoning_effort"], "low");
+    }
+
+    #[tokio::test]
+    async fn test_warm_up_loads_ollama_model() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/chat")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "model": "qwen3:1.7b",
+                "messages": [],
+                "stream": false,
+                "think": false,
+                "keep_alive": -1,
+                "options": { "num_ctx": 8192 }
+            })))
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"model":"qwen3:1.7b","message":{"role":"assistant","content":""},"done_reason":"load","done":true}"#)
+            .create_async()
+            .await;
+
+        let mut profile = ProviderProfile {
+            api_url: format!("{}/api/chat", server.url()),
+            ollama: Some(crate::config::OllamaOptions {
+                keep_alive: Some(serde_json::json!(-1)),
+                num_ctx: Some(8192),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        warm_up(&profile).await.unwrap();
+        mock.assert_async().await;
+
+        // 关闭预热或其他平台不发送请求
+        profile.ollama.as_mut().unwrap().warm_up = false;
+        warm_up(&profile).await.unwrap();
+        profile.platform = PlatformType::ChatGPT;
+        warm_up(&profile).await.unwrap();
+        mock.expect(1).assert_async().await;
+    }
+
+    #[test]
+    fn test_context_and_history_examples() {
+        let context = TranslationContext {
+            domain: Some("software".to_string()),
+            before: Some("  ".to_string()),
+            after: Some("the tests before committing.".to_string()),
+            examples: vec![("Build".to_string(), "构建".to_string())],
+        };
+        let payload = build_request_payload(
+            "Run",
+            Lang::Zh,
+            Lang::En,
+            &AppConfig::default(),
+            &ProviderProfile::default(),
+            &context,
+        )
+        .unwrap();
+        let json = serde_json::to_value(payload).unwrap();
+        let messages = json["messages"].as_array().unwrap();
+
+        let roles: Vec<&str> = messages.iter().filter_map(|m| m["role"].as_str()).collect();
+        assert_eq!(roles, ["system", "user", "assistant", "user"]);
+        let system = messages[0]["content"].as_str().unwrap();
+        assert!(system.contains("Domain: software"));
+        assert!(system.contains("Text after: the tests before committing."));
+        assert!(!system.contains("Text before"));
+        assert!(messages[1]["content"].as_str().unwrap().contains("Build"));
+        assert_eq!(messages[2]["content"], "构建");
+        assert!(messages[3]["content"].as_str().unwrap().contains("Run"));
+    }
+
+    #[tokio::test]
+    async fn test_refine_translation_keeps_conversation() {
+        let mut server = mockito::Server::new_async().await;
+        let first = server
+            .mock("POST", "/api/chat")
+            .match_body(mockito::Matcher::Regex("更正式".to_string()))
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"message":{"role":"assistant","content":"Good day"}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let db_path =
+            std::env::temp_dir().join(format!("tauri_yi_refine_{}.db", uuid::Uuid::new_v4()));
+        std::fs::write(&db_path, b"").unwrap();
+        let config = AppConfig {
+            provider: ProviderProfile {
+                api_url: format!("{}/api/chat", server.url()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let state = AppState::new(config, std::env::temp_dir().join("unused.json"), db_path);
+        let database = state.database().await.unwrap();
+        let original_id = database
+            .save_translation("你好", "Hi", "zh", "en", &TranslationMeta::default())
+            .await
+            .unwrap();
+
+        let revision = refine_translation(&state, &original_id, "更正式")
+            .await
+            .unwrap();
+        first.assert_async().await;
+        assert_eq!(revision.translated_text, "Good day");
+        assert_eq!(revision.parent_id.as_deref(), Some(original_id.as_str()));
+        assert_eq!(revision.source_text, "你好");
+
+        // 基于修订继续调整时，对话包含原始翻译和之前的修订
+        let second = server
+            .mock("POST", "/api/chat")
+            .match_body(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::Regex("Hi".to_string()),
+                mockito::Matcher::Regex("Good day".to_string()),
+                mockito::Matcher::Regex("更简短".to_string()),
+            ]))
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"message":{"role":"assistant","content":"Hello"}}"#)
+            .create_async()
+            .await;
+        let revision = refine_translation(&state, &revision.id, "更简短")
+            .await
+            .unwrap();
+        second.assert_async().await;
+        assert_eq!(revision.parent_id.as_deref(), Some(original_id.as_str()));
+        assert_eq!(database.get_revisions(&original_id).await.unwrap().len(), 2);
+
+        assert!(database.delete_translation(&original_id).await.unwrap());
+        assert!(database
+            .get_revisions(&original_id)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_translate_dictionary_for_short_text() {
+        let mut server = mockito::Server::new_async().await;
+        let content = serde_json::json!({
+            "word": "run",
+            "pronunciation": "/rʌn/",
+            "senses": [{ "partOfSpeech": "verb", "translations": ["运行", "跑"] }],
+            "examples": [{ "source": "Run the tests.", "translation": "运行测试。" }]
+        })
+        .to_string();
+        let mock = server
+            .mock("POST", "/api/chat")
+            .match_body(mockito::Matcher::PartialJson(
+                serde_json::json!({ "format": "json" }),
+            ))
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({ "message": { "role": "assistant", "content": content } })
+                    .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let db_path =
+            std::env::temp_dir().join(format!("tauri_yi_dict_{}.db", uuid::Uuid::new_v4()));
+        std::fs::write(&db_path, b"").unwrap();
+        let config = AppConfig {
+            provider: ProviderProfile {
+                api_url: format!("{}/api/chat", server.url()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let state = AppState::new(config, std::env::temp_dir().join("unused.json"), db_path);
+        let result = translate_dictionary(&state, "Run".to_string(), Lang::Zh, Lang::En)
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(result.translation, "运行");
+        let entry = result.entry.unwrap();
+        assert_eq!(entry.pronunciation.as_deref(), Some("/rʌn/"));
+        assert_eq!(entry.examples.len(), 1);
+        let history = state
+            .database()
+            .await
+            .unwrap()
+            .get_translation_history(None, None)
+            .await
+            .unwrap();
+        assert_eq!(history[0].translated_text, "运行");
+        assert_eq!(result.id.as_deref(), Some(history[0].id.as_str()));
+
+        // 离线词典中有释义时不再请求翻译服务
+        let entries = [crate::dictionary::DictEntry {
+            word: "run".to_string(),
+            phonetic: Some("/rʌn/".to_string()),
+            definition: "v. 跑\nn. 奔跑".to_string(),
+        }];
+        state
+            .database()
+            .await
+            .unwrap()
+            .import_dictionary("basic", "basic.csv", &entries)
+            .await
+            .unwrap();
+        let result = translate_dictionary(&state, "Run".to_string(), Lang::Zh, Lang::En)
+            .await
+            .unwrap();
+        mock.assert_async().await;
+        assert_eq!(result.translation, "v. 跑");
+        assert!(result.id.is_none());
+        assert!(result.entry.is_none());
+        assert_eq!(result.definitions[0].dictionary, "basic");
     }
 }
@@ -0,0 +1,251 @@
+use anyhow::{anyhow, bail, Result};
+use serde::Serialize;
+
+use super::{ChatMessage, TranslationResponse};
+use crate::config::SamplingOptions;
+
+pub const ANTHROPIC_VERSION: &str = "2023-06-01";
+// Messages API 要求必须提供 max_tokens
+const DEFAULT_MAX_TOKENS: u32 = 4096;
+
+/// Anthropic Messages API 请求体，系统提示词是顶层字段而不是一条消息
+#[derive(Serialize, Debug)]
+pub struct MessagesRequest {
+    model: String,
+    max_tokens: u32,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    system: String,
+    messages: Vec<ChatMessage>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f64>,
+}
+
+pub fn build_request(
+    model: &str,
+    system_prompt: String,
+    messages: Vec<ChatMessage>,
+    stream: bool,
+    sampling: &SamplingOptions,
+) -> MessagesRequest {
+    MessagesRequest {
+        model: model.to_string(),
+        max_tokens: sampling.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+        system: system_prompt,
+        messages,
+        stream,
+        temperature: sampling.temperature,
+        top_p: sampling.top_p,
+    }
+}
+
+// Anthropic 使用 x-api-key 而不是 Authorization 头
+pub fn authorize(request: reqwest::RequestBuilder, api_key: &str) -> reqwest::RequestBuilder {
+    request
+        .header("x-api-key", api_key)
+        .header("anthropic-version", ANTHROPIC_VERSION)
+}
+
+pub fn parse_response(json: &serde_json::Value) -> Result<TranslationResponse> {
+    let blocks = json["content"]
+        .as_array()
+        .ok_or_else(|| anyhow!("Failed to parse Anthropic response"))?;
+    let texts: Vec<&str> = blocks
+        .iter()
+        .filter(|block| block["type"] == "text")
+        .filter_map(|block| block["text"].as_str())
+        .collect();
+    if texts.is_empty() {
+        bail!("Anthropic response contains no text content");
+    }
+
+    Ok(TranslationResponse {
+        text: texts.concat(),
+        prompt_tokens: json["usage"]["input_tokens"].as_i64(),
+        completion_tokens: json["usage"]["output_tokens"].as_i64(),
+    })
+}
+
+/// 读取流式响应，拼接所有文本增量
+pub async fn read_event_stream(mut response: reqwest::Response) -> Result<TranslationResponse> {
+    let mut stream = EventStream::default();
+    while let Some(chunk) = response.chunk().await? {
+        stream.feed(&chunk)?;
+    }
+    stream.finish()
+}
+
+/// Server-Sent Events 解析状态
+///
+/// 数据块可能在任意位置被截断（包括 UTF-8 字符中间），因此按字节缓存，
+/// 只处理以空行结束的完整事件。
+#[derive(Default)]
+pub struct EventStream {
+    buffer: Vec<u8>,
+    text: String,
+    input_tokens: Option<i64>,
+    output_tokens: Option<i64>,
+}
+
+impl EventStream {
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<()> {
+        self.buffer
+            .extend(chunk.iter().copied().filter(|&byte| byte != b'\r'));
+        while let Some(end) = self.buffer.windows(2).position(|w| w == b"\n\n") {
+            let event: Vec<u8> = self.buffer.drain(..end + 2).collect();
+            self.handle_event(&String::from_utf8_lossy(&event))?;
+        }
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<TranslationResponse> {
+        // 最后一个事件后可能没有空行
+        if !self.buffer.is_empty() {
+            let event = std::mem::take(&mut self.buffer);
+            self.handle_event(&String::from_utf8_lossy(&event))?;
+        }
+        Ok(TranslationResponse {
+            text: self.text,
+            prompt_tokens: self.input_tokens,
+            completion_tokens: self.output_tokens,
+        })
+    }
+
+    fn handle_event(&mut self, event: &str) -> Result<()> {
+        let data: Vec<&str> = event
+            .lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .map(str::trim_start)
+            .collect();
+        if data.is_empty() {
+            return Ok(());
+        }
+        let json: serde_json::Value = serde_json::from_str(&data.join("\n"))?;
+
+        match json["type"].as_str() {
+            Some("message_start") => {
+                let usage = &json["message"]["usage"];
+                self.input_tokens = usage["input_tokens"].as_i64();
+                self.output_tokens = usage["output_tokens"].as_i64();
+            }
+            Some("content_block_delta") if json["delta"]["type"] == "text_delta" => {
+                if let Some(text) = json["delta"]["text"].as_str() {
+                    self.text.push_str(text);
+                }
+            }
+            Some("message_delta") => {
+                if let Some(output_tokens) = json["usage"]["output_tokens"].as_i64() {
+                    self.output_tokens = Some(output_tokens);
+                }
+            }
+            Some("error") => bail!(
+                "Anthropic stream error: {}",
+                json["error"]["message"].as_str().unwrap_or("unknown error")
+            ),
+            // ping、content_block_start 等事件不包含译文
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::test_support::{error_message, mock_provider, translate};
+    use crate::config::PlatformType;
+
+    #[tokio::test]
+    async fn test_messages_request_and_response() {
+        let (mut server, mut profile) =
+            mock_provider(PlatformType::Anthropic, "/v1/messages").await;
+        profile.model_name = "claude-3-5-haiku-latest".to_string();
+        let mock = server
+            .mock("POST", "/v1/messages")
+            .match_header("x-api-key", "test-key")
+            .match_header("anthropic-version", ANTHROPIC_VERSION)
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "model": "claude-3-5-haiku-latest",
+                "max_tokens": DEFAULT_MAX_TOKENS,
+                "messages": [{ "role": "user" }]
+            })))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"id":"msg_1","type":"message","role":"assistant",
+                    "content":[{"type":"text","text":"Hello"}],
+                    "usage":{"input_tokens":25,"output_tokens":3}}"#,
+            )
+            .create_async()
+            .await;
+
+        let result = translate(&profile).await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(result.text, "Hello");
+        assert_eq!(result.prompt_tokens, Some(25));
+        assert_eq!(result.completion_tokens, Some(3));
+    }
+
+    #[tokio::test]
+    async fn test_error_response() {
+        let message = error_message(
+            PlatformType::Anthropic,
+            401,
+            r#"{"type":"error","error":{"type":"authentication_error","message":"invalid x-api-key"}}"#,
+        )
+        .await;
+        assert!(message.contains("invalid x-api-key"));
+    }
+
+    #[tokio::test]
+    async fn test_streaming_events() {
+        let (mut server, mut profile) =
+            mock_provider(PlatformType::Anthropic, "/v1/messages").await;
+        profile.stream = true;
+        let mock = server
+            .mock("POST", "/v1/messages")
+            .match_header("x-api-key", "test-key")
+            .match_header("anthropic-version", ANTHROPIC_VERSION)
+            .match_body(mockito::Matcher::PartialJson(
+                serde_json::json!({ "stream": true }),
+            ))
+            .with_header("content-type", "text/event-stream")
+            .with_body(concat!(
+                "event: message_start\n",
+                "data: {\"type\":\"message_start\",\"message\":{\"usage\":{\"input_tokens\":25,\"output_tokens\":1}}}\n\n",
+                "event: ping\n",
+                "data: {\"type\":\"ping\"}\n\n",
+                "event: content_block_delta\n",
+                "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"Hel\"}}\n\n",
+                "event: content_block_delta\n",
+                "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"lo\"}}\n\n",
+                "event: message_delta\n",
+                "data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"end_turn\"},\"usage\":{\"output_tokens\":4}}\n\n",
+                "event: message_stop\n",
+                "data: {\"type\":\"message_stop\"}\n\n",
+            ))
+            .create_async()
+            .await;
+
+        let result = translate(&profile).await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(result.text, "Hello");
+        assert_eq!(result.prompt_tokens, Some(25));
+        assert_eq!(result.completion_tokens, Some(4));
+    }
+
+    #[test]
+    fn test_event_stream_split_chunks() {
+        let mut stream = EventStream::default();
+        let event = "data: {\"type\":\"content_block_delta\",\"delta\":{\"type\":\"text_delta\",\"text\":\"你好\"}}\r\n\r\n";
+        // 在多字节字符中间截断
+        let split = event.find("你").unwrap() + 1;
+        stream.feed(&event.as_bytes()[..split]).unwrap();
+        stream.feed(&event.as_bytes()[split..]).unwrap();
+        assert_eq!(stream.finish().unwrap().text, "你好");
+    }
+}
@@ -0,0 +1,171 @@
+use anyhow::{anyhow, bail, Result};
+use reqwest::StatusCode;
+use serde::Serialize;
+
+use super::TranslationResponse;
+use crate::config::ProviderProfile;
+use crate::lang::Lang;
+
+pub const FREE_API_URL: &str = "https://api-free.deepl.com/v2/translate";
+pub const PRO_API_URL: &str = "https://api.deepl.com/v2/translate";
+
+/// DeepL /v2/translate 请求体
+#[derive(Serialize, Debug)]
+pub struct TranslateRequest {
+    text: Vec<String>,
+    target_lang: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_lang: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    formality: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    glossary_id: Option<String>,
+}
+
+// 源语言代码，自动检测时不传
+fn source_code(lang: Lang) -> Option<&'static str> {
+    match lang {
+        Lang::Zh => Some("ZH"),
+        Lang::En => Some("EN"),
+        Lang::Ja => Some("JA"),
+        Lang::Ko => Some("KO"),
+        Lang::Auto => None,
+    }
+}
+
+// 目标语言代码，英语和中文需要指定变体
+fn target_code(lang: Lang) -> Result<&'static str> {
+    match lang {
+        Lang::Zh => Ok("ZH-HANS"),
+        Lang::En => Ok("EN-US"),
+        Lang::Ja => Ok("JA"),
+        Lang::Ko => Ok("KO"),
+        Lang::Auto => bail!("DeepL 的目标语言不能为自动检测"),
+    }
+}
+
+pub fn build_request(
+    profile: &ProviderProfile,
+    text: &str,
+    target_lang: Lang,
+    source_lang: Lang,
+) -> Result<TranslateRequest> {
+    let source_lang = source_code(source_lang);
+    let glossary_id = profile.glossary_id.clone().filter(|id| !id.is_empty());
+    if glossary_id.is_some() && source_lang.is_none() {
+        bail!("使用 DeepL 术语表时必须指定源语言");
+    }
+
+    Ok(TranslateRequest {
+        text: vec![text.to_string()],
+        target_lang: target_code(target_lang)?,
+        source_lang,
+        formality: profile.formality.clone().filter(|f| !f.is_empty()),
+        glossary_id,
+    })
+}
+
+/// 未配置 API 地址时，根据 API Key 选择免费版或专业版接口
+///
+/// 免费版的 API Key 以 `:fx` 结尾。
+pub fn endpoint(profile: &ProviderProfile) -> &str {
+    if !profile.api_url.trim().is_empty() {
+        &profile.api_url
+    } else if profile.api_key.ends_with(":fx") {
+        FREE_API_URL
+    } else {
+        PRO_API_URL
+    }
+}
+
+pub fn authorize(request: reqwest::RequestBuilder, api_key: &str) -> reqwest::RequestBuilder {
+    request.header("Authorization", format!("DeepL-Auth-Key {}", api_key))
+}
+
+pub fn parse_response(json: &serde_json::Value) -> Result<TranslationResponse> {
+    let text = json["translations"][0]["text"]
+        .as_str()
+        .ok_or_else(|| anyhow!("Failed to parse DeepL response"))?;
+
+    Ok(TranslationResponse {
+        text: text.to_string(),
+        prompt_tokens: None,
+        completion_tokens: None,
+    })
+}
+
+// DeepL 的部分错误只通过状态码区分
+pub fn error_message(status: StatusCode, body: &str) -> String {
+    match status.as_u16() {
+        403 => "DeepL API Key 无效或与接口地址不匹配".to_string(),
+        429 => "DeepL 请求过于频繁，请稍后重试".to_string(),
+        456 => "DeepL 翻译额度已用尽".to_string(),
+        _ => super::error_message(body),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::test_support::{error_message, mock_provider, translate};
+    use crate::config::PlatformType;
+
+    #[test]
+    fn test_endpoint_selection() {
+        let mut profile = ProviderProfile {
+            api_key: "test-key:fx".to_string(),
+            api_url: String::new(),
+            platform: PlatformType::DeepL,
+            ..Default::default()
+        };
+        assert_eq!(endpoint(&profile), FREE_API_URL);
+        profile.api_key = "pro-key".to_string();
+        assert_eq!(endpoint(&profile), PRO_API_URL);
+        profile.api_url = "http://localhost:8080/v2/translate".to_string();
+        assert_eq!(endpoint(&profile), "http://localhost:8080/v2/translate");
+    }
+
+    #[tokio::test]
+    async fn test_translate_request() {
+        let (mut server, mut profile) = mock_provider(PlatformType::DeepL, "/v2/translate").await;
+        profile.formality = Some("more".to_string());
+        profile.glossary_id = Some("glossary-1".to_string());
+        let mock = server
+            .mock("POST", "/v2/translate")
+            .match_header("authorization", "DeepL-Auth-Key test-key")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "text": ["你好"],
+                "target_lang": "EN-US",
+                "source_lang": "ZH",
+                "formality": "more",
+                "glossary_id": "glossary-1"
+            })))
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"translations":[{"detected_source_language":"ZH","text":"Hello"}]}"#)
+            .create_async()
+            .await;
+
+        let result = translate(&profile).await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(result.text, "Hello");
+    }
+
+    #[tokio::test]
+    async fn test_quota_exceeded() {
+        let message =
+            error_message(PlatformType::DeepL, 456, r#"{"message":"Quota Exceeded"}"#).await;
+        assert!(message.contains("额度已用尽"));
+    }
+
+    #[test]
+    fn test_glossary_requires_source_lang() {
+        let profile = ProviderProfile {
+            platform: PlatformType::DeepL,
+            glossary_id: Some("glossary-1".to_string()),
+            ..Default::default()
+        };
+        assert!(build_request(&profile, "你好", Lang::En, Lang::Auto).is_err());
+        assert!(build_request(&profile, "你好", Lang::Auto, Lang::Zh).is_err());
+    }
+}
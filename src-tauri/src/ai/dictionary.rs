@@ -0,0 +1,132 @@
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::lang::{self, Lang};
+
+// 超过该长度的文本按普通翻译处理
+const MAX_CHARS: usize = 32;
+const MAX_WORDS: usize = 3;
+
+/// 单词或短语的词典释义
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DictionaryEntry {
+    pub word: String,
+    /// 音标或拼音
+    #[serde(default)]
+    pub pronunciation: Option<String>,
+    pub senses: Vec<Sense>,
+    #[serde(default)]
+    pub examples: Vec<Example>,
+}
+
+/// 按词性划分的释义，`translations` 按常用程度排列
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Sense {
+    #[serde(rename = "partOfSpeech", default)]
+    pub part_of_speech: Option<String>,
+    pub translations: Vec<String>,
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Example {
+    pub source: String,
+    pub translation: String,
+}
+
+impl DictionaryEntry {
+    /// 最常用的译文
+    pub fn primary_translation(&self) -> Option<&str> {
+        self.senses
+            .iter()
+            .flat_map(|sense| sense.translations.iter())
+            .map(String::as_str)
+            .next()
+    }
+}
+
+/// 是否按词典模式处理：单行且不超过三个单词的短文本，中日韩文字按字数判断
+pub fn is_short_text(text: &str) -> bool {
+    text.trim().chars().count() <= MAX_CHARS && lang::is_word_or_phrase(text, MAX_WORDS)
+}
+
+/// 要求模型按固定结构输出 JSON 的系统提示词
+pub fn system_prompt(source_lang: Lang, target_lang: Lang) -> String {
+    let source = match source_lang {
+        Lang::Auto => "the detected language",
+        lang => lang.to_full_name(),
+    };
+    format!(
+        r#"You are a bilingual dictionary. The user sends a word or short phrase in {source}.
+Reply with a single JSON object and nothing else, using this structure:
+{{"word": "<the word>", "pronunciation": "<IPA or pinyin, or null>",
+"senses": [{{"partOfSpeech": "<noun, verb, ...>", "translations": ["<{target} translation>", "..."], "explanation": "<short explanation in {target}>"}}],
+"examples": [{{"source": "<example sentence in {source}>", "translation": "<{target} translation>"}}]}}
+List the most common senses first, with up to 5 translations per sense and up to 3 examples."#,
+        source = source,
+        target = target_lang.to_full_name(),
+    )
+}
+
+/// 解析并校验模型返回的词典 JSON，兼容包裹在 Markdown 代码块中的输出
+pub fn parse_entry(text: &str) -> Result<DictionaryEntry> {
+    let start = text
+        .find('{')
+        .ok_or_else(|| anyhow!("词典结果中没有 JSON 对象"))?;
+    let end = text
+        .rfind('}')
+        .filter(|&end| end > start)
+        .ok_or_else(|| anyhow!("词典结果中没有 JSON 对象"))?;
+    let mut entry: DictionaryEntry =
+        serde_json::from_str(&text[start..=end]).map_err(|e| anyhow!("词典结果格式无效: {}", e))?;
+
+    // 去除空白的译文和没有译文的释义
+    for sense in &mut entry.senses {
+        sense.translations.retain(|t| !t.trim().is_empty());
+    }
+    entry.senses.retain(|sense| !sense.translations.is_empty());
+    entry
+        .examples
+        .retain(|e| !e.source.trim().is_empty() && !e.translation.trim().is_empty());
+    if entry.senses.is_empty() {
+        bail!("词典结果中没有译文");
+    }
+    Ok(entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_short_text() {
+        assert!(is_short_text(" Run "));
+        assert!(is_short_text("take off"));
+        assert!(is_short_text("你好"));
+        assert!(!is_short_text("Run the tests before committing"));
+        assert!(!is_short_text("Run\nSet"));
+        assert!(!is_short_text("  "));
+        // 不含空格的中日文句子不按词典模式处理
+        assert!(!is_short_text("请在提交之前运行所有测试"));
+        assert!(!is_short_text("今日はいい天気ですね"));
+    }
+
+    #[test]
+    fn test_parse_entry() {
+        let text = r#"```json
+{"word": "run", "pronunciation": "/rʌn/",
+ "senses": [{"partOfSpeech": "verb", "translations": ["跑", " ", "运行"], "explanation": "快速移动"},
+            {"partOfSpeech": "noun", "translations": []}],
+ "examples": [{"source": "Run the tests.", "translation": "运行测试。"}]}
+```"#;
+        let entry = parse_entry(text).unwrap();
+        assert_eq!(entry.senses.len(), 1);
+        assert_eq!(entry.senses[0].translations, ["跑", "运行"]);
+        assert_eq!(entry.primary_translation(), Some("跑"));
+        assert_eq!(entry.examples.len(), 1);
+
+        assert!(parse_entry("跑").is_err());
+        assert!(parse_entry(r#"{"word": "run", "senses": []}"#).is_err());
+    }
+}
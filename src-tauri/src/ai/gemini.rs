@@ -0,0 +1,218 @@
+use anyhow::{anyhow, bail, Result};
+use serde::Serialize;
+
+use super::{ChatMessage, TranslationResponse};
+use crate::config::SamplingOptions;
+
+/// Gemini generateContent 请求体
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateContentRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system_instruction: Option<Content>,
+    contents: Vec<Content>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    generation_config: Option<GenerationConfig>,
+}
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct GenerationConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_output_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thinking_config: Option<ThinkingConfig>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ThinkingConfig {
+    thinking_budget: u32,
+}
+
+#[derive(Serialize, Debug)]
+struct Content {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    role: Option<String>,
+    parts: Vec<Part>,
+}
+
+#[derive(Serialize, Debug)]
+struct Part {
+    text: String,
+}
+
+/// `disable_thinking` 为 true 时将思考预算设为 0，仅对支持思考的模型生效
+pub fn build_request(
+    system_prompt: String,
+    messages: Vec<ChatMessage>,
+    sampling: &SamplingOptions,
+    disable_thinking: bool,
+) -> GenerateContentRequest {
+    let generation_config = (!sampling.is_empty() || disable_thinking).then(|| GenerationConfig {
+        temperature: sampling.temperature,
+        top_p: sampling.top_p,
+        max_output_tokens: sampling.max_tokens,
+        seed: sampling.seed,
+        thinking_config: disable_thinking.then_some(ThinkingConfig { thinking_budget: 0 }),
+    });
+
+    GenerateContentRequest {
+        system_instruction: (!system_prompt.is_empty()).then(|| Content {
+            role: None,
+            parts: vec![Part {
+                text: system_prompt,
+            }],
+        }),
+        // Gemini 中模型回复的角色为 model
+        contents: messages
+            .into_iter()
+            .map(|message| Content {
+                role: Some(match message.role.as_str() {
+                    "assistant" => "model".to_string(),
+                    _ => message.role,
+                }),
+                parts: vec![Part {
+                    text: message.content,
+                }],
+            })
+            .collect(),
+        generation_config,
+    }
+}
+
+/// 根据 API 地址和模型名称生成请求地址
+///
+/// `api_url` 可以是 `https://generativelanguage.googleapis.com/v1beta` 这样的基础地址，
+/// 也可以是已经包含 `:generateContent` 的完整地址。
+pub fn endpoint(api_url: &str, model_name: &str) -> String {
+    if api_url.contains(":generateContent") {
+        api_url.to_string()
+    } else {
+        format!(
+            "{}/models/{}:generateContent",
+            api_url.trim_end_matches('/'),
+            model_name
+        )
+    }
+}
+
+// Gemini 通过请求头传递 API Key，放在查询参数中会随请求地址出现在错误信息里
+pub fn authorize(request: reqwest::RequestBuilder, api_key: &str) -> reqwest::RequestBuilder {
+    request.header("x-goog-api-key", api_key)
+}
+
+pub fn parse_response(json: &serde_json::Value) -> Result<TranslationResponse> {
+    let candidate = match json["candidates"].get(0) {
+        Some(candidate) => candidate,
+        None => match json["promptFeedback"]["blockReason"].as_str() {
+            Some(reason) => bail!("Gemini blocked the prompt: {}", reason),
+            None => bail!("Failed to parse Gemini response"),
+        },
+    };
+
+    let texts: Vec<&str> = candidate["content"]["parts"]
+        .as_array()
+        .map(|parts| parts.iter().filter_map(|p| p["text"].as_str()).collect())
+        .unwrap_or_default();
+    if texts.is_empty() {
+        return Err(match candidate["finishReason"].as_str() {
+            Some(reason) => anyhow!("Gemini returned no text, finish reason: {}", reason),
+            None => anyhow!("Failed to parse Gemini response"),
+        });
+    }
+
+    let usage = &json["usageMetadata"];
+    Ok(TranslationResponse {
+        text: texts.concat(),
+        prompt_tokens: usage["promptTokenCount"].as_i64(),
+        completion_tokens: usage["candidatesTokenCount"].as_i64(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::test_support::{error_message, mock_provider, translate};
+    use crate::config::{PlatformType, ThinkingMode};
+
+    #[test]
+    fn test_endpoint() {
+        assert_eq!(
+            endpoint(
+                "https://generativelanguage.googleapis.com/v1beta/",
+                "gemini-2.0-flash"
+            ),
+            "https://generativelanguage.googleapis.com/v1beta/models/gemini-2.0-flash:generateContent"
+        );
+        let full = "https://example.com/v1/models/custom:generateContent";
+        assert_eq!(endpoint(full, "ignored"), full);
+    }
+
+    #[tokio::test]
+    async fn test_generate_content() {
+        let (mut server, mut profile) = mock_provider(PlatformType::Gemini, "/v1beta").await;
+        profile.model_name = "gemini-2.0-flash".to_string();
+        profile.sampling = SamplingOptions {
+            temperature: Some(0.2),
+            max_tokens: Some(1024),
+            ..Default::default()
+        };
+        profile.thinking = Some(ThinkingMode::Disabled);
+        let mock = server
+            .mock("POST", "/v1beta/models/gemini-2.0-flash:generateContent")
+            .match_header("x-goog-api-key", "test-key")
+            .match_query(mockito::Matcher::Missing)
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "contents": [{ "role": "user" }],
+                "generationConfig": {
+                    "temperature": 0.2,
+                    "maxOutputTokens": 1024,
+                    "thinkingConfig": { "thinkingBudget": 0 }
+                }
+            })))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"candidates":[{"content":{"role":"model","parts":[{"text":"Hel"},{"text":"lo"}]},
+                    "finishReason":"STOP"}],
+                    "usageMetadata":{"promptTokenCount":12,"candidatesTokenCount":2,"totalTokenCount":14}}"#,
+            )
+            .create_async()
+            .await;
+
+        let result = translate(&profile).await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(result.text, "Hello");
+        assert_eq!(result.prompt_tokens, Some(12));
+        assert_eq!(result.completion_tokens, Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_error_response() {
+        let message = error_message(
+            PlatformType::Gemini,
+            400,
+            r#"{"error":{"code":400,"message":"API key not valid. Please pass a valid API key.","status":"INVALID_ARGUMENT"}}"#,
+        )
+        .await;
+        assert!(message.contains("API key not valid"));
+    }
+
+    #[test]
+    fn test_blocked_responses() {
+        let blocked = serde_json::json!({ "promptFeedback": { "blockReason": "SAFETY" } });
+        let error = parse_response(&blocked).unwrap_err();
+        assert!(error.to_string().contains("SAFETY"));
+
+        let empty = serde_json::json!({ "candidates": [{ "finishReason": "RECITATION" }] });
+        let error = parse_response(&empty).unwrap_err();
+        assert!(error.to_string().contains("RECITATION"));
+    }
+}
@@ -0,0 +1,105 @@
+use anyhow::{anyhow, bail, Result};
+use serde::Serialize;
+
+use super::TranslationResponse;
+use crate::config::ProviderProfile;
+use crate::lang::Lang;
+
+/// LibreTranslate /translate 请求体
+#[derive(Serialize, Debug)]
+pub struct TranslateRequest {
+    q: String,
+    source: &'static str,
+    target: &'static str,
+    format: &'static str,
+    // 自建服务通常不需要 API Key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_key: Option<String>,
+}
+
+pub fn build_request(
+    profile: &ProviderProfile,
+    text: &str,
+    target_lang: Lang,
+    source_lang: Lang,
+) -> Result<TranslateRequest> {
+    if let Lang::Auto = target_lang {
+        bail!("LibreTranslate 的目标语言不能为自动检测");
+    }
+
+    Ok(TranslateRequest {
+        q: text.to_string(),
+        // LibreTranslate 的语言代码与本程序相同，自动检测同样使用 auto
+        source: source_lang.into(),
+        target: target_lang.into(),
+        format: "text",
+        api_key: Some(profile.api_key.clone()).filter(|key| !key.is_empty()),
+    })
+}
+
+pub fn parse_response(json: &serde_json::Value) -> Result<TranslationResponse> {
+    let text = json["translatedText"]
+        .as_str()
+        .ok_or_else(|| anyhow!("Failed to parse LibreTranslate response"))?;
+
+    Ok(TranslationResponse {
+        text: text.to_string(),
+        prompt_tokens: None,
+        completion_tokens: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ai::request_translation;
+    use crate::ai::test_support::{error_message, mock_provider};
+    use crate::config::{AppConfig, PlatformType};
+    use crate::lang::Lang;
+
+    #[tokio::test]
+    async fn test_translate_request() {
+        let (mut server, mut profile) =
+            mock_provider(PlatformType::LibreTranslate, "/translate").await;
+        profile.api_key.clear();
+        let mock = server
+            .mock("POST", "/translate")
+            .match_header("authorization", mockito::Matcher::Missing)
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "q": "こんにちは",
+                "source": "auto",
+                "target": "zh",
+                "format": "text"
+            })))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"detectedLanguage":{"confidence":90,"language":"ja"},"translatedText":"你好"}"#,
+            )
+            .create_async()
+            .await;
+
+        let result = request_translation(
+            &AppConfig::default(),
+            &profile,
+            "こんにちは",
+            Lang::Zh,
+            Lang::Auto,
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(result.text, "你好");
+    }
+
+    #[tokio::test]
+    async fn test_error_response() {
+        let message = error_message(
+            PlatformType::LibreTranslate,
+            400,
+            r#"{"error":"ko is not supported"}"#,
+        )
+        .await;
+        assert!(message.contains("ko is not supported"));
+    }
+}
@@ -0,0 +1,362 @@
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+
+use super::openai_compatible;
+use crate::config::{self, PlatformType, ProviderProfile};
+
+/// 已安装或可用的模型
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct ModelInfo {
+    pub name: String,
+    /// 模型文件大小（字节），仅 Ollama 提供
+    pub size: Option<u64>,
+    #[serde(rename = "modifiedAt")]
+    pub modified_at: Option<String>,
+    #[serde(rename = "parameterSize")]
+    pub parameter_size: Option<String>,
+    #[serde(rename = "quantizationLevel")]
+    pub quantization_level: Option<String>,
+}
+
+/// Ollama /api/show 返回的模型详情
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct ModelDetails {
+    pub name: String,
+    pub family: Option<String>,
+    #[serde(rename = "parameterSize")]
+    pub parameter_size: Option<String>,
+    #[serde(rename = "quantizationLevel")]
+    pub quantization_level: Option<String>,
+    #[serde(rename = "contextLength")]
+    pub context_length: Option<u64>,
+    /// 模型能力，如 completion、thinking、vision
+    pub capabilities: Vec<String>,
+}
+
+/// 拉取模型的进度，`total` 和 `completed` 只在下载分层时存在
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct PullProgress {
+    pub name: String,
+    pub status: String,
+    pub digest: Option<String>,
+    pub total: Option<u64>,
+    pub completed: Option<u64>,
+}
+
+/// 从翻译接口地址推导 Ollama 服务地址
+///
+/// 支持 `http://localhost:11434/api/chat` 和 `http://localhost:11434/v1/chat/completions`。
+pub fn ollama_base_url(api_url: &str) -> String {
+    let api_url = api_url.trim().trim_end_matches('/');
+    ["/api/", "/v1/"]
+        .iter()
+        .find_map(|marker| api_url.find(marker).map(|index| &api_url[..index]))
+        .unwrap_or(api_url)
+        .to_string()
+}
+
+/// 从 /chat/completions 地址推导 /models 地址
+pub fn openai_models_url(api_url: &str) -> String {
+    let api_url = api_url.trim().trim_end_matches('/');
+    let base = api_url.strip_suffix("/chat/completions").unwrap_or(api_url);
+    format!("{}/models", base)
+}
+
+async fn read_json(response: reqwest::Response) -> Result<serde_json::Value> {
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        bail!("请求失败 ({}): {}", status, super::error_message(&body));
+    }
+    Ok(response.json().await?)
+}
+
+/// 列出翻译服务可用的模型
+///
+/// Ollama 使用 /api/tags 列出已安装的模型，OpenAI 格式的接口使用 /models。
+pub async fn list_models(profile: &ProviderProfile) -> Result<Vec<ModelInfo>> {
+    let client = &config::REQUEST_CLIENT;
+    match profile.platform {
+        PlatformType::OLLama => {
+            let url = format!("{}/api/tags", ollama_base_url(&profile.api_url));
+            let json = read_json(client.get(url).send().await?).await?;
+            parse_ollama_tags(&json)
+        }
+        PlatformType::DeepSeek | PlatformType::ChatGPT | PlatformType::OpenAICompatible => {
+            let request = client.get(openai_models_url(&profile.api_url));
+            let request = match &profile.platform {
+                PlatformType::OpenAICompatible => openai_compatible::authorize(
+                    request,
+                    profile,
+                    &profile.openai_compatible.clone().unwrap_or_default(),
+                ),
+                _ => request.header("Authorization", format!("Bearer {}", profile.api_key)),
+            };
+            let json = read_json(request.send().await?).await?;
+            parse_openai_models(&json)
+        }
+        _ => bail!("{} 不支持获取模型列表", profile.platform.as_str()),
+    }
+}
+
+fn parse_ollama_tags(json: &serde_json::Value) -> Result<Vec<ModelInfo>> {
+    let models = json["models"]
+        .as_array()
+        .ok_or_else(|| anyhow!("Failed to parse OLLama model list"))?;
+    Ok(models
+        .iter()
+        .filter_map(|model| {
+            let details = &model["details"];
+            Some(ModelInfo {
+                name: model["name"].as_str()?.to_string(),
+                size: model["size"].as_u64(),
+                modified_at: model["modified_at"].as_str().map(str::to_string),
+                parameter_size: details["parameter_size"].as_str().map(str::to_string),
+                quantization_level: details["quantization_level"].as_str().map(str::to_string),
+            })
+        })
+        .collect())
+}
+
+fn parse_openai_models(json: &serde_json::Value) -> Result<Vec<ModelInfo>> {
+    let models = json["data"]
+        .as_array()
+        .ok_or_else(|| anyhow!("Failed to parse model list"))?;
+    let mut models: Vec<ModelInfo> = models
+        .iter()
+        .filter_map(|model| {
+            Some(ModelInfo {
+                name: model["id"].as_str()?.to_string(),
+                ..Default::default()
+            })
+        })
+        .collect();
+    models.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(models)
+}
+
+/// 获取 Ollama 模型详情
+pub async fn show_model(profile: &ProviderProfile, name: &str) -> Result<ModelDetails> {
+    if profile.platform != PlatformType::OLLama {
+        bail!("只有 OLLama 支持查看模型详情");
+    }
+    let url = format!("{}/api/show", ollama_base_url(&profile.api_url));
+    let response = config::REQUEST_CLIENT
+        .post(url)
+        .json(&serde_json::json!({ "model": name }))
+        .send()
+        .await?;
+    let json = read_json(response).await?;
+    Ok(parse_ollama_show(name, &json))
+}
+
+fn parse_ollama_show(name: &str, json: &serde_json::Value) -> ModelDetails {
+    let details = &json["details"];
+    // 上下文长度的键名带有模型架构前缀，如 qwen3.context_length
+    let context_length = json["model_info"].as_object().and_then(|info| {
+        info.iter()
+            .find(|(key, _)| key.ends_with(".context_length"))
+            .and_then(|(_, value)| value.as_u64())
+    });
+
+    ModelDetails {
+        name: name.to_string(),
+        family: details["family"].as_str().map(str::to_string),
+        parameter_size: details["parameter_size"].as_str().map(str::to_string),
+        quantization_level: details["quantization_level"].as_str().map(str::to_string),
+        context_length,
+        capabilities: json["capabilities"]
+            .as_array()
+            .map(|list| {
+                list.iter()
+                    .filter_map(|c| c.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default(),
+    }
+}
+
+/// 拉取 Ollama 模型，每收到一条进度就调用一次 `on_progress`
+pub async fn pull_model(
+    profile: &ProviderProfile,
+    name: &str,
+    mut on_progress: impl FnMut(PullProgress),
+) -> Result<()> {
+    if profile.platform != PlatformType::OLLama {
+        bail!("只有 OLLama 支持拉取模型");
+    }
+    let url = format!("{}/api/pull", ollama_base_url(&profile.api_url));
+    let mut response = config::REQUEST_CLIENT
+        .post(url)
+        .json(&serde_json::json!({ "model": name, "stream": true }))
+        .send()
+        .await?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        bail!("拉取模型失败 ({}): {}", status, super::error_message(&body));
+    }
+
+    // 响应为逐行的 JSON，数据块可能在任意位置被截断
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut success = false;
+    while let Some(chunk) = response.chunk().await? {
+        buffer.extend_from_slice(&chunk);
+        while let Some(end) = buffer.iter().position(|&byte| byte == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=end).collect();
+            success |= handle_pull_line(name, &line, &mut on_progress)?;
+        }
+    }
+    success |= handle_pull_line(name, &buffer, &mut on_progress)?;
+
+    if !success {
+        bail!("拉取模型 {} 未完成", name);
+    }
+    Ok(())
+}
+
+// 返回是否已拉取完成
+fn handle_pull_line(
+    name: &str,
+    line: &[u8],
+    on_progress: &mut impl FnMut(PullProgress),
+) -> Result<bool> {
+    let line = String::from_utf8_lossy(line);
+    if line.trim().is_empty() {
+        return Ok(false);
+    }
+    let json: serde_json::Value = serde_json::from_str(line.trim())?;
+    if let Some(error) = json["error"].as_str() {
+        bail!("拉取模型失败: {}", error);
+    }
+
+    let status = json["status"].as_str().unwrap_or_default().to_string();
+    let success = status == "success";
+    on_progress(PullProgress {
+        name: name.to_string(),
+        status,
+        digest: json["digest"].as_str().map(str::to_string),
+        total: json["total"].as_u64(),
+        completed: json["completed"].as_u64(),
+    });
+    Ok(success)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::test_support::mock_provider;
+
+    #[test]
+    fn test_base_urls() {
+        assert_eq!(
+            ollama_base_url("http://localhost:11434/api/chat"),
+            "http://localhost:11434"
+        );
+        assert_eq!(
+            ollama_base_url("http://localhost:11434/v1/chat/completions"),
+            "http://localhost:11434"
+        );
+        assert_eq!(
+            openai_models_url("https://api.deepseek.com/chat/completions"),
+            "https://api.deepseek.com/models"
+        );
+        assert_eq!(
+            openai_models_url("https://api.openai.com/v1/"),
+            "https://api.openai.com/v1/models"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_ollama_models() {
+        let (mut server, profile) = mock_provider(PlatformType::OLLama, "/api/chat").await;
+        server
+            .mock("GET", "/api/tags")
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"models":[{"name":"qwen3:1.7b","modified_at":"2025-05-01T10:00:00Z","size":1359293444,
+                    "digest":"abc","details":{"family":"qwen3","parameter_size":"2.0B","quantization_level":"Q4_K_M"}}]}"#,
+            )
+            .create_async()
+            .await;
+
+        let models = list_models(&profile).await.unwrap();
+
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].name, "qwen3:1.7b");
+        assert_eq!(models[0].size, Some(1359293444));
+        assert_eq!(models[0].parameter_size.as_deref(), Some("2.0B"));
+    }
+
+    #[tokio::test]
+    async fn test_list_openai_models() {
+        let (mut server, profile) =
+            mock_provider(PlatformType::ChatGPT, "/v1/chat/completions").await;
+        let mock = server
+            .mock("GET", "/v1/models")
+            .match_header("authorization", "Bearer test-key")
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"object":"list","data":[{"id":"gpt-4o-mini","object":"model"},{"id":"gpt-4o","object":"model"}]}"#,
+            )
+            .create_async()
+            .await;
+
+        let models = list_models(&profile).await.unwrap();
+
+        mock.assert_async().await;
+        let names: Vec<&str> = models.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["gpt-4o", "gpt-4o-mini"]);
+    }
+
+    #[test]
+    fn test_parse_ollama_show() {
+        let json = serde_json::json!({
+            "details": { "family": "qwen3", "parameter_size": "2.0B", "quantization_level": "Q4_K_M" },
+            "model_info": { "general.architecture": "qwen3", "qwen3.context_length": 40960 },
+            "capabilities": ["completion", "tools", "thinking"]
+        });
+        let details = parse_ollama_show("qwen3:1.7b", &json);
+        assert_eq!(details.context_length, Some(40960));
+        assert_eq!(details.capabilities, ["completion", "tools", "thinking"]);
+    }
+
+    #[tokio::test]
+    async fn test_pull_model_progress() {
+        let (mut server, profile) = mock_provider(PlatformType::OLLama, "/api/chat").await;
+        server
+            .mock("POST", "/api/pull")
+            .match_body(mockito::Matcher::PartialJson(
+                serde_json::json!({ "model": "qwen3:1.7b" }),
+            ))
+            .with_body(
+                "{\"status\":\"pulling manifest\"}\n\
+                 {\"status\":\"pulling abc\",\"digest\":\"sha256:abc\",\"total\":100,\"completed\":50}\n\
+                 {\"status\":\"success\"}\n",
+            )
+            .create_async()
+            .await;
+
+        let mut progress = Vec::new();
+        pull_model(&profile, "qwen3:1.7b", |p| progress.push(p))
+            .await
+            .unwrap();
+
+        assert_eq!(progress.len(), 3);
+        assert_eq!(progress[1].completed, Some(50));
+        assert_eq!(progress[2].status, "success");
+    }
+
+    #[tokio::test]
+    async fn test_pull_model_error() {
+        let (mut server, profile) = mock_provider(PlatformType::OLLama, "/api/chat").await;
+        server
+            .mock("POST", "/api/pull")
+            .with_body("{\"status\":\"pulling manifest\"}\n{\"error\":\"pull model manifest: file does not exist\"}\n")
+            .create_async()
+            .await;
+
+        let error = pull_model(&profile, "missing", |_| {}).await.unwrap_err();
+        assert!(error.to_string().contains("file does not exist"));
+    }
+}
@@ -0,0 +1,127 @@
+use anyhow::Result;
+
+use super::ChatRequest;
+use crate::config::{OpenAICompatibleOptions, ProviderProfile};
+
+/// 将附加字段合并到请求体中，同名字段以附加字段为准
+pub fn build_request(
+    request: ChatRequest,
+    options: &OpenAICompatibleOptions,
+) -> Result<serde_json::Value> {
+    let mut body = serde_json::to_value(request)?;
+    if let Some(body) = body.as_object_mut() {
+        for (name, value) in &options.extra_body {
+            body.insert(name.clone(), value.clone());
+        }
+    }
+    Ok(body)
+}
+
+/// 按配置添加认证头、附加请求头和查询参数
+///
+/// API Key 为空时不发送认证头，便于连接不需要认证的本地服务。
+pub fn authorize(
+    mut request: reqwest::RequestBuilder,
+    profile: &ProviderProfile,
+    options: &OpenAICompatibleOptions,
+) -> reqwest::RequestBuilder {
+    if !profile.api_key.is_empty() && !options.auth_header.is_empty() {
+        request = request.header(
+            &options.auth_header,
+            options.auth_format.replace("{{key}}", &profile.api_key),
+        );
+    }
+    for (name, value) in &options.headers {
+        request = request.header(name, value);
+    }
+    if !options.query.is_empty() {
+        request = request.query(&options.query);
+    }
+    request
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use crate::ai::test_support::{mock_provider, translate};
+    use crate::config::{OpenAICompatibleOptions, PlatformType};
+
+    const CHAT_RESPONSE: &str = r#"{"choices":[{"message":{"role":"assistant","content":"Hello"}}],
+        "usage":{"prompt_tokens":20,"completion_tokens":1}}"#;
+
+    #[tokio::test]
+    async fn test_azure_style_request() {
+        let path = "/openai/deployments/gpt-4o-mini/chat/completions";
+        let (mut server, mut profile) = mock_provider(PlatformType::OpenAICompatible, path).await;
+        profile.model_name = "gpt-4o-mini".to_string();
+        profile.openai_compatible = Some(OpenAICompatibleOptions {
+            auth_header: "api-key".to_string(),
+            auth_format: "{{key}}".to_string(),
+            headers: HashMap::from([("X-Title".to_string(), "Tauri Yi".to_string())]),
+            query: HashMap::from([("api-version".to_string(), "2024-10-21".to_string())]),
+            extra_body: serde_json::json!({ "temperature": 0.2, "max_tokens": 512 })
+                .as_object()
+                .cloned()
+                .unwrap(),
+        });
+        // 记录请求体，检查除提示词以外的字段与预期完全一致
+        let body = Arc::new(Mutex::new(serde_json::Value::Null));
+        let received = body.clone();
+        let mock = server
+            .mock("POST", path)
+            .match_query(mockito::Matcher::UrlEncoded(
+                "api-version".to_string(),
+                "2024-10-21".to_string(),
+            ))
+            .match_header("api-key", "test-key")
+            .match_header("authorization", mockito::Matcher::Missing)
+            .match_header("x-title", "Tauri Yi")
+            .with_header("content-type", "application/json")
+            .with_body_from_request(move |request| {
+                *received.lock().unwrap() =
+                    serde_json::from_slice(request.body().unwrap()).unwrap();
+                CHAT_RESPONSE.into()
+            })
+            .create_async()
+            .await;
+
+        let result = translate(&profile).await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(result.text, "Hello");
+        assert_eq!(result.prompt_tokens, Some(20));
+        let mut body = body.lock().unwrap().take();
+        let messages = body.as_object_mut().unwrap().remove("messages").unwrap();
+        assert_eq!(messages[0]["role"], "system");
+        assert_eq!(messages[1]["role"], "user");
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "model": "gpt-4o-mini",
+                "stream": false,
+                "temperature": 0.2,
+                "max_tokens": 512
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_default_bearer_auth() {
+        let (mut server, mut profile) =
+            mock_provider(PlatformType::OpenAICompatible, "/v1/chat/completions").await;
+        profile.openai_compatible = Some(OpenAICompatibleOptions::default());
+        let mock = server
+            .mock("POST", "/v1/chat/completions")
+            .match_header("authorization", "Bearer test-key")
+            .with_header("content-type", "application/json")
+            .with_body(CHAT_RESPONSE)
+            .create_async()
+            .await;
+
+        translate(&profile).await.unwrap();
+
+        mock.assert_async().await;
+    }
+}
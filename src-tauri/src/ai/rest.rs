@@ -0,0 +1,208 @@
+use anyhow::{anyhow, Result};
+use reqwest::Method;
+
+use super::TranslationResponse;
+use crate::config::{self, ProviderProfile, RestApiOptions};
+use crate::lang::Lang;
+
+// 语言代码，可通过 langCodes 覆盖默认值
+fn lang_code(options: &RestApiOptions, lang: Lang) -> String {
+    let code: &str = lang.into();
+    options
+        .lang_codes
+        .get(code)
+        .cloned()
+        .unwrap_or_else(|| code.to_string())
+}
+
+// 替换模板中的占位符，escape 用于对替换值进行转义
+//
+// 只扫描一遍模板，替换后的值不会再被当作模板，避免待翻译文本中的 `{{key}}` 被替换为 API Key。
+fn render(template: &str, values: &[(&str, &str)], escape: impl Fn(&str) -> String) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let placeholder = &rest[start..];
+        let value = values.iter().find_map(|(name, value)| {
+            placeholder
+                .strip_prefix("{{")
+                .and_then(|p| p.strip_prefix(name))
+                .and_then(|p| p.strip_prefix("}}"))
+                .map(|after| (value, after))
+        });
+        match value {
+            Some((value, after)) => {
+                rendered.push_str(&escape(value));
+                rest = after;
+            }
+            None => {
+                rendered.push_str("{{");
+                rest = &placeholder[2..];
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+// 按 JSON 字符串规则转义，不包含两侧引号
+fn escape_json(value: &str) -> String {
+    let quoted = serde_json::Value::String(value.to_string()).to_string();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+/// 根据配置的模板构建请求
+pub fn build_request(
+    profile: &ProviderProfile,
+    text: &str,
+    target_lang: Lang,
+    source_lang: Lang,
+) -> Result<reqwest::RequestBuilder> {
+    let options = profile
+        .rest_api
+        .as_ref()
+        .ok_or_else(|| anyhow!("未配置通用 REST 翻译接口"))?;
+    let from = lang_code(options, source_lang);
+    let to = lang_code(options, target_lang);
+    let values = [
+        ("text", text),
+        ("from", from.as_str()),
+        ("to", to.as_str()),
+        ("key", profile.api_key.as_str()),
+    ];
+
+    let method = if options.method.trim().is_empty() {
+        Method::POST
+    } else {
+        Method::from_bytes(options.method.trim().to_uppercase().as_bytes())
+            .map_err(|_| anyhow!("无效的请求方法: {}", options.method))?
+    };
+
+    let mut request = config::REQUEST_CLIENT.request(method, &profile.api_url);
+    for (name, value) in &options.headers {
+        request = request.header(name, render(value, &values, str::to_string));
+    }
+    let query: Vec<(&String, String)> = options
+        .query
+        .iter()
+        .map(|(name, value)| (name, render(value, &values, str::to_string)))
+        .collect();
+    if !query.is_empty() {
+        request = request.query(&query);
+    }
+    if let Some(body) = options.body.as_ref().filter(|body| !body.is_empty()) {
+        let body = render(body, &values, escape_json);
+        // 提前校验模板渲染结果，避免把格式错误的请求发给服务端
+        let body: serde_json::Value = serde_json::from_str(&body)
+            .map_err(|e| anyhow!("REST 请求体模板不是有效的 JSON: {}", e))?;
+        request = request.json(&body);
+    }
+    Ok(request)
+}
+
+pub fn parse_response(
+    json: &serde_json::Value,
+    profile: &ProviderProfile,
+) -> Result<TranslationResponse> {
+    let result_path = profile
+        .rest_api
+        .as_ref()
+        .map(|options| options.result_path.as_str())
+        .unwrap_or_default();
+    let pointer = format!("/{}", result_path.replace('.', "/"));
+    let text = json
+        .pointer(&pointer)
+        .and_then(|value| value.as_str())
+        .ok_or_else(|| anyhow!("响应中未找到译文: {}", result_path))?;
+
+    Ok(TranslationResponse {
+        text: text.to_string(),
+        prompt_tokens: None,
+        completion_tokens: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::ai::request_translation;
+    use crate::ai::test_support::mock_provider;
+    use crate::config::{AppConfig, PlatformType};
+
+    #[tokio::test]
+    async fn test_template_request() {
+        let (mut server, mut profile) =
+            mock_provider(PlatformType::RestApi, "/language/translate/v2").await;
+        profile.rest_api = Some(RestApiOptions {
+            method: String::new(),
+            headers: HashMap::from([("x-client".to_string(), "tauri-yi".to_string())]),
+            query: HashMap::from([("key".to_string(), "{{key}}".to_string())]),
+            body: Some(
+                r#"{"q": "{{text}}", "source": "{{from}}", "target": "{{to}}"}"#.to_string(),
+            ),
+            result_path: "data.translations.0.translatedText".to_string(),
+            lang_codes: HashMap::from([("zh".to_string(), "zh-CN".to_string())]),
+        });
+        let mock = server
+            .mock("POST", "/language/translate/v2")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "key".to_string(),
+                "test-key".to_string(),
+            ))
+            .match_header("x-client", "tauri-yi")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "q": "他说：\"你好\"",
+                "source": "zh-CN",
+                "target": "en"
+            })))
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data":{"translations":[{"translatedText":"He said: \"Hello\""}]}}"#)
+            .create_async()
+            .await;
+
+        let result = request_translation(
+            &AppConfig::default(),
+            &profile,
+            "他说：\"你好\"",
+            Lang::En,
+            Lang::Zh,
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(result.text, "He said: \"Hello\"");
+    }
+
+    #[test]
+    fn test_render_does_not_expand_values() {
+        let values = [("text", "{{key}} {{to}}"), ("to", "en"), ("key", "secret")];
+        assert_eq!(
+            render("{{text}} -> {{to}} {{unknown}}", &values, str::to_string),
+            "{{key}} {{to}} -> en {{unknown}}"
+        );
+        assert_eq!(
+            render(r#"{"q": "{{text}}"}"#, &values, escape_json),
+            r#"{"q": "{{key}} {{to}}"}"#
+        );
+    }
+
+    #[test]
+    fn test_result_path() {
+        let profile = ProviderProfile {
+            platform: PlatformType::RestApi,
+            rest_api: Some(RestApiOptions {
+                result_path: "0.translations.0.text".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let bing = serde_json::json!([{ "translations": [{ "text": "Hello", "to": "en" }] }]);
+        assert_eq!(parse_response(&bing, &profile).unwrap().text, "Hello");
+        assert!(parse_response(&serde_json::json!({}), &profile).is_err());
+    }
+}
@@ -1,65 +1,74 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use tauri::window::{Color, Effect, EffectState, EffectsBuilder};
-use tauri::Emitter;
-use tauri::{AppHandle, LogicalSize, Manager, Size, WebviewUrl, WebviewWindowBuilder};
-use tauri_plugin_clipboard_manager::ClipboardExt;
-use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
+use tauri::{
+    AppHandle, LogicalSize, Manager, PhysicalPosition, Size, WebviewUrl, WebviewWindow,
+    WebviewWindowBuilder, WindowEvent,
+};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+use crate::config::{self, PopupPlacement, ScreenPosition, ShortcutConfig};
+use crate::position::{self, MonitorArea};
+use crate::state::AppState;
+use crate::{actions, app_window, shortcut};
 
-use crate::config;
-use crate::utils;
+pub const MAIN_WINDOW: &str = "main";
 
 #[cfg(desktop)]
 pub fn setup_desktop(app_handle: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-    let ctrl_j_shortcut = Shortcut::new(Some(Modifiers::META), Code::KeyJ);
     app_handle.plugin(
         tauri_plugin_global_shortcut::Builder::new()
-            .with_handler(move |app_handle, shortcut, _event| {
-                if shortcut == &ctrl_j_shortcut && _event.state == ShortcutState::Pressed {
-                    handle_shortcut_press(app_handle);
+            .with_handler(move |app_handle, shortcut, event| {
+                if event.state != ShortcutState::Pressed {
+                    return;
+                }
+                let config = app_handle.state::<AppState>().config();
+                if let Some(action) = shortcut::find_action(&config.shortcuts, shortcut) {
+                    actions::run(app_handle, action);
                 }
             })
             .build(),
     )?;
 
-    app_handle.global_shortcut().register(ctrl_j_shortcut)?;
+    // 快捷键注册失败不影响启动，可以在设置中修改后重新注册
+    let shortcuts = app_handle.state::<AppState>().config().shortcuts;
+    if let Err(e) = register_shortcuts(app_handle, &shortcuts) {
+        eprintln!("{}", e);
+    }
     Ok(())
 }
 
+/// 注册配置中的全局快捷键，替换之前注册的全部快捷键
 #[cfg(desktop)]
-fn handle_shortcut_press(app_handle: &AppHandle) {
-    let main_window = app_handle.get_webview_window("main");
-
-    if let Some(window) = main_window {
-        let primary_monitor = window.primary_monitor().unwrap().unwrap();
-        let scale_factor = primary_monitor.scale_factor();
-        // 计算窗口的最终位置
-        let (final_x, final_y) = utils::calculate_window_position(primary_monitor, scale_factor);
-
-        window
-            .set_position(tauri::PhysicalPosition::new(final_x, final_y))
-            .unwrap();
-        window.show().unwrap();
-        window.set_focus().unwrap();
-
-        match app_handle.clipboard().read_text() {
-            Ok(content) => {
-                
-                println!("Clipboard content received:\n{}", content);
-                app_handle
-                    .emit("clipboard-content", content)
-                    .unwrap_or_else(|e| eprintln!("Failed to emit event: {}", e));
-            }
-            Err(err) => {
-                eprintln!("Error getting clipboard content: {:?}", err);
-            }
-        }
-    } else {
-        println!("Main window not found");
+pub fn register_shortcuts(app_handle: &AppHandle, config: &ShortcutConfig) -> anyhow::Result<()> {
+    let bindings = shortcut::parse_config(config)?;
+    let global_shortcut = app_handle.global_shortcut();
+    global_shortcut.unregister_all()?;
+    // 逐个注册，某个快捷键被占用时不影响其他快捷键
+    let failed: Vec<String> = bindings
+        .into_iter()
+        .filter_map(|(action, shortcut)| {
+            let e = global_shortcut.register(shortcut).err()?;
+            Some(format!(
+                "{}（{}）: {}",
+                action.accelerator(config),
+                action.label(),
+                e
+            ))
+        })
+        .collect();
+    if !failed.is_empty() {
+        anyhow::bail!(
+            "快捷键注册失败，可能已被其他程序占用: {}",
+            failed.join("；")
+        );
     }
-    println!("Ctrl-J Detected!");
+    Ok(())
 }
 
 pub fn build_main_window(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-    let win_builder = WebviewWindowBuilder::new(app, "main", WebviewUrl::default())
+    app.manage(MainWindowState::default());
+    let win_builder = WebviewWindowBuilder::new(app, MAIN_WINDOW, WebviewUrl::default())
         .inner_size(config::INIT_WEIDTH, config::INIT_HEIGHT);
 
     let window = win_builder.build().unwrap();
@@ -74,34 +83,146 @@ pub fn build_main_window(app: &AppHandle) -> Result<(), Box<dyn std::error::Erro
     window.set_decorations(false)?;
     window.set_maximizable(false)?;
     window.set_minimizable(false)?;
-    window.set_max_size(Some(Size::Logical(LogicalSize::new(
-        config::INIT_WEIDTH,
-        config::INIT_HEIGHT,
-    ))))?;
     window.set_min_size(Some(Size::Logical(LogicalSize::new(
         config::INIT_WEIDTH,
         config::INIT_HEIGHT,
     ))))?;
+    set_fixed_size(&window, true)?;
+
+    let app_handle = app.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Focused(false) = event {
+            on_main_window_blur(&app_handle);
+        }
+    });
     Ok(())
 }
 
-// 专门监控main窗口状态
-pub fn monitor_main_window_state(
-    app_handle: AppHandle
-){
-    tokio::spawn(async move{
-        println!("monitor_main_window_state");
-        loop {
-            let main_window = app_handle.get_webview_window("main");
-            if let Some(window) = main_window.as_ref() {
-                // 检查窗口是否被聚焦
-                if !window.is_focused().unwrap() {
-                    // 隐藏窗口
-                    window.hide().unwrap();
-                }
-                
-            }
-            tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+/// 主窗口状态，固定后失去焦点时不隐藏，可以调整大小
+#[derive(Default)]
+pub struct MainWindowState {
+    pinned: AtomicBool,
+}
+
+/// 主窗口是否已固定
+pub fn is_pinned(app_handle: &AppHandle) -> bool {
+    app_handle
+        .try_state::<MainWindowState>()
+        .is_some_and(|state| state.pinned.load(Ordering::Relaxed))
+}
+
+/// 固定或取消固定主窗口
+///
+/// 固定后窗口置顶、失去焦点时不隐藏，并可以调整大小，调整后的大小保存在配置中，下次固定时恢复；
+/// 取消固定后恢复默认大小。
+pub fn set_pinned(app_handle: &AppHandle, pinned: bool) -> anyhow::Result<()> {
+    let window = app_handle
+        .get_webview_window(MAIN_WINDOW)
+        .ok_or_else(|| anyhow::anyhow!("主窗口不存在"))?;
+    let state = app_handle.state::<MainWindowState>();
+    if state.pinned.swap(pinned, Ordering::Relaxed) == pinned {
+        return Ok(());
+    }
+
+    if pinned {
+        set_fixed_size(&window, false)?;
+        let geometry = app_handle
+            .state::<AppState>()
+            .config()
+            .windows
+            .get(MAIN_WINDOW)
+            .copied();
+        if let Some(geometry) = geometry {
+            window.set_size(LogicalSize::new(geometry.width, geometry.height))?;
         }
+    } else {
+        app_window::save_geometry(&window)?;
+        window.set_size(LogicalSize::new(config::INIT_WEIDTH, config::INIT_HEIGHT))?;
+        set_fixed_size(&window, true)?;
+    }
+    window.set_always_on_top(pinned)?;
+    Ok(())
+}
+
+// 未固定时窗口大小固定为默认大小
+fn set_fixed_size(window: &WebviewWindow, fixed: bool) -> tauri::Result<()> {
+    window.set_resizable(!fixed)?;
+    let max_size = fixed.then_some(Size::Logical(LogicalSize::new(
+        config::INIT_WEIDTH,
+        config::INIT_HEIGHT,
+    )));
+    window.set_max_size(max_size)
+}
+
+// 主窗口失去焦点时隐藏，固定时只保存调整后的大小
+fn on_main_window_blur(app_handle: &AppHandle) {
+    let Some(window) = app_handle.get_webview_window(MAIN_WINDOW) else {
+        return;
+    };
+    if is_pinned(app_handle) {
+        if let Err(e) = app_window::save_geometry(&window) {
+            eprintln!("保存窗口大小失败: {}", e);
+        }
+    } else if app_handle.state::<AppState>().config().auto_hide {
+        hide_main_window(&window);
+    }
+}
+
+/// 隐藏主窗口，显示位置为 `LastPosition` 时记录隐藏前的位置
+pub fn hide_main_window(window: &WebviewWindow) {
+    if let Err(e) = save_last_position(window) {
+        eprintln!("保存窗口位置失败: {}", e);
+    }
+    window
+        .hide()
+        .unwrap_or_else(|e| eprintln!("隐藏窗口失败: {}", e));
+}
+
+fn save_last_position(window: &WebviewWindow) -> anyhow::Result<()> {
+    let state = window.state::<AppState>();
+    let mut config = state.config();
+    if config.popup_placement != PopupPlacement::LastPosition || !window.is_visible()? {
+        return Ok(());
+    }
+    let position = window.outer_position()?;
+    let position = Some(ScreenPosition {
+        x: position.x,
+        y: position.y,
     });
+    if config.last_popup_position == position {
+        return Ok(());
+    }
+    config.last_popup_position = position;
+    state.update_config(config)
+}
+
+/// 按配置的显示位置移动主窗口，位置根据鼠标所在的显示器计算
+pub fn place_main_window(app_handle: &AppHandle, window: &WebviewWindow) -> anyhow::Result<()> {
+    let config = app_handle.state::<AppState>().config();
+    let monitors: Vec<MonitorArea> = window
+        .available_monitors()?
+        .iter()
+        .map(|monitor| MonitorArea {
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width,
+            height: monitor.size().height,
+            scale_factor: monitor.scale_factor(),
+        })
+        .collect();
+    let cursor = app_handle.cursor_position()?;
+    // 窗口可能在缩放比例不同的显示器上，按逻辑大小计算
+    let size = window
+        .outer_size()?
+        .to_logical::<f64>(window.scale_factor()?);
+    let position = position::popup_position(
+        config.popup_placement,
+        &monitors,
+        (cursor.x, cursor.y),
+        (size.width, size.height),
+        config.last_popup_position,
+    )
+    .ok_or_else(|| anyhow::anyhow!("没有可用的显示器"))?;
+    window.set_position(PhysicalPosition::new(position.x, position.y))?;
+    Ok(())
 }
@@ -0,0 +1,160 @@
+use anyhow::Result;
+use serde::Deserialize;
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder, WindowEvent};
+
+use crate::config::WindowGeometry;
+use crate::state::AppState;
+
+// 独立窗口的最小尺寸
+const MIN_WIDTH: f64 = 360.0;
+const MIN_HEIGHT: f64 = 400.0;
+
+/// 按需创建的独立窗口，与主窗口使用同一个页面，通过 URL 中的 `#history`、`#settings` 区分
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AppWindow {
+    History,
+    Settings,
+}
+
+impl AppWindow {
+    pub const ALL: [AppWindow; 2] = [AppWindow::History, AppWindow::Settings];
+
+    /// 窗口标签，同一标签的窗口只会创建一个
+    pub fn label(self) -> &'static str {
+        match self {
+            AppWindow::History => "history",
+            AppWindow::Settings => "settings",
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            AppWindow::History => "翻译历史",
+            AppWindow::Settings => "设置",
+        }
+    }
+
+    fn default_size(self) -> (f64, f64) {
+        match self {
+            AppWindow::History => (480.0, 600.0),
+            AppWindow::Settings => (440.0, 640.0),
+        }
+    }
+}
+
+/// 打开独立窗口，窗口已打开时显示并聚焦，首次打开时恢复上次关闭时的位置和大小
+pub fn open(app_handle: &AppHandle, kind: AppWindow) -> Result<()> {
+    if let Some(window) = app_handle.get_webview_window(kind.label()) {
+        window.unminimize()?;
+        window.show()?;
+        window.set_focus()?;
+        return Ok(());
+    }
+
+    let geometry = app_handle
+        .state::<AppState>()
+        .config()
+        .windows
+        .get(kind.label())
+        .copied();
+    let url = WebviewUrl::App(format!("index.html#{}", kind.label()).into());
+    let mut builder = WebviewWindowBuilder::new(app_handle, kind.label(), url)
+        .title(kind.title())
+        .resizable(true)
+        .min_inner_size(MIN_WIDTH, MIN_HEIGHT);
+    builder = match geometry {
+        Some(geometry) => builder
+            .inner_size(geometry.width, geometry.height)
+            .position(geometry.x, geometry.y),
+        None => {
+            let (width, height) = kind.default_size();
+            builder.inner_size(width, height).center()
+        }
+    };
+    let window = builder.build()?;
+    // 上次所在的显示器已断开时居中显示
+    if geometry.is_some() && !is_on_screen(&window)? {
+        window.center()?;
+    }
+
+    let handle = window.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::CloseRequested { .. } = event {
+            if let Err(e) = save_geometry(&handle) {
+                eprintln!("保存窗口位置失败: {}", e);
+            }
+        }
+    });
+    window.set_focus()?;
+    Ok(())
+}
+
+/// 保存所有已打开的独立窗口的位置和大小，退出应用前调用
+pub fn save_all(app_handle: &AppHandle) {
+    for kind in AppWindow::ALL {
+        if let Some(window) = app_handle.get_webview_window(kind.label()) {
+            if let Err(e) = save_geometry(&window) {
+                eprintln!("保存窗口位置失败: {}", e);
+            }
+        }
+    }
+}
+
+/// 保存窗口的位置和大小，最大化或最小化时不保存，下次打开时仍使用之前的位置和大小
+pub fn save_geometry(window: &WebviewWindow) -> Result<()> {
+    if window.is_maximized()? || window.is_minimized()? {
+        return Ok(());
+    }
+    let scale_factor = window.scale_factor()?;
+    let position = window.outer_position()?.to_logical::<f64>(scale_factor);
+    let size = window.inner_size()?.to_logical::<f64>(scale_factor);
+    let geometry = WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    };
+
+    let state = window.state::<AppState>();
+    let mut config = state.config();
+    if config.windows.get(window.label()) == Some(&geometry) {
+        return Ok(());
+    }
+    config.windows.insert(window.label().to_string(), geometry);
+    state.update_config(config)
+}
+
+// 窗口左上角是否在某个显示器内
+fn is_on_screen(window: &WebviewWindow) -> Result<bool> {
+    let position = window.outer_position()?;
+    Ok(window.available_monitors()?.iter().any(|monitor| {
+        let origin = monitor.position();
+        let size = monitor.size();
+        contains(
+            (origin.x, origin.y),
+            (size.width, size.height),
+            (position.x, position.y),
+        )
+    }))
+}
+
+fn contains(origin: (i32, i32), size: (u32, u32), point: (i32, i32)) -> bool {
+    let (x, y) = (i64::from(point.0), i64::from(point.1));
+    let (left, top) = (i64::from(origin.0), i64::from(origin.1));
+    x >= left && y >= top && x < left + i64::from(size.0) && y < top + i64::from(size.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains() {
+        assert!(contains((0, 0), (1920, 1080), (100, 100)));
+        assert!(!contains((0, 0), (1920, 1080), (1920, 100)));
+        // 主显示器左侧的显示器坐标为负数
+        assert!(contains((-1280, 0), (1280, 1024), (-1000, 10)));
+        assert!(!contains((-1280, 0), (1280, 1024), (-1000, -10)));
+    }
+}
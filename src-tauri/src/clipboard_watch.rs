@@ -0,0 +1,236 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::actions;
+use crate::config::{ClipboardWatchConfig, WatchOutput};
+use crate::notification::{self, TranslationResult};
+use crate::state::AppState;
+
+// 最多记录的本程序写入剪贴板的内容条数
+const MAX_IGNORED: usize = 8;
+// 检查剪贴板的最短间隔
+const MIN_POLL_INTERVAL_MS: u64 = 100;
+
+/// 判断剪贴板中的新内容是否需要翻译
+///
+/// 内容需要保持不变超过防抖时间，且与上一次处理的内容不同、不是本程序写入的内容、
+/// 不短于最小长度时才会翻译。
+#[derive(Debug, Default)]
+pub struct ClipboardFilter {
+    // 上一次处理的内容，无论是否翻译
+    last: Option<String>,
+    // 等待防抖的内容及首次读取的时间
+    pending: Option<(String, Instant)>,
+    // 本程序写入剪贴板的内容
+    ignored: Vec<String>,
+}
+
+impl ClipboardFilter {
+    /// 把当前内容视为已处理，开启监听时剪贴板中已有的内容不翻译
+    pub fn reset(&mut self, text: Option<String>) {
+        self.last = text;
+        self.pending = None;
+    }
+
+    /// 记录本程序写入剪贴板的内容
+    pub fn ignore(&mut self, text: String) {
+        if self.ignored.len() >= MAX_IGNORED {
+            self.ignored.remove(0);
+        }
+        self.ignored.push(text);
+    }
+
+    /// 每次读取剪贴板后调用，返回需要翻译的内容
+    ///
+    /// `from_own_window` 为 true 表示内容是从本程序的窗口中复制的，不翻译。
+    pub fn poll(
+        &mut self,
+        text: Option<String>,
+        from_own_window: bool,
+        now: Instant,
+        config: &ClipboardWatchConfig,
+    ) -> Option<String> {
+        let text = text?;
+        if self.last.as_ref() == Some(&text) {
+            self.pending = None;
+            return None;
+        }
+        if from_own_window {
+            self.reset(Some(text));
+            return None;
+        }
+
+        let since = match &self.pending {
+            Some((pending, since)) if pending == &text => *since,
+            _ => {
+                self.pending = Some((text.clone(), now));
+                now
+            }
+        };
+        if now.duration_since(since) < Duration::from_millis(config.debounce_ms) {
+            return None;
+        }
+
+        self.reset(Some(text.clone()));
+        if let Some(index) = self.ignored.iter().position(|ignored| ignored == &text) {
+            self.ignored.remove(index);
+            return None;
+        }
+        if text.trim().chars().count() < config.min_length {
+            return None;
+        }
+        Some(text)
+    }
+}
+
+/// 剪贴板监听状态，通过 `app.manage` 交给 Tauri 托管
+#[derive(Default)]
+pub struct ClipboardWatchState {
+    filter: Mutex<ClipboardFilter>,
+}
+
+/// 记录本程序写入剪贴板的内容，监听剪贴板时不翻译这些内容
+pub fn ignore(app_handle: &AppHandle, text: &str) {
+    if let Some(state) = app_handle.try_state::<ClipboardWatchState>() {
+        state
+            .filter
+            .lock()
+            .expect("Clipboard filter lock failed")
+            .ignore(text.to_string());
+    }
+}
+
+/// 开启或关闭剪贴板监听，并保存到配置文件
+pub fn set_enabled(app_handle: &AppHandle, enabled: bool) -> Result<()> {
+    let state = app_handle.state::<AppState>();
+    let mut config = state.config();
+    config.clipboard_watch.enabled = enabled;
+    state.update_config(config)
+}
+
+/// 启动剪贴板监听，关闭监听时只检查配置，不读取剪贴板
+pub fn start(app_handle: AppHandle) {
+    app_handle.manage(ClipboardWatchState::default());
+    tauri::async_runtime::spawn(async move {
+        let mut watching = false;
+        loop {
+            let config = app_handle.state::<AppState>().config().clipboard_watch;
+            let interval = config.poll_interval_ms.max(MIN_POLL_INTERVAL_MS);
+            tokio::time::sleep(Duration::from_millis(interval)).await;
+            if !config.enabled {
+                watching = false;
+                continue;
+            }
+
+            let text = app_handle.clipboard().read_text().ok();
+            let from_own_window = app_handle
+                .webview_windows()
+                .values()
+                .any(|window| window.is_focused().unwrap_or(false));
+            let text = {
+                let state = app_handle.state::<ClipboardWatchState>();
+                let mut filter = state.filter.lock().expect("Clipboard filter lock failed");
+                if watching {
+                    filter.poll(text, from_own_window, Instant::now(), &config)
+                } else {
+                    filter.reset(text);
+                    watching = true;
+                    None
+                }
+            };
+            if let Some(text) = text {
+                if let Err(e) = show_translation(&app_handle, text, config.output).await {
+                    eprintln!("剪贴板翻译失败: {}", e);
+                }
+            }
+        }
+    });
+}
+
+async fn show_translation(app_handle: &AppHandle, text: String, output: WatchOutput) -> Result<()> {
+    match output {
+        WatchOutput::Popup => actions::show_in_popup(app_handle, text),
+        WatchOutput::Notification => match actions::translate(app_handle, text.clone()).await {
+            Ok(translation) => {
+                let result = TranslationResult {
+                    id: translation.id,
+                    source_text: text,
+                    translated_text: translation.text,
+                };
+                notification::show_result(app_handle, "翻译结果", result)?;
+            }
+            Err(e) => {
+                notification::show_error(app_handle, "剪贴板翻译失败", &e);
+                return Err(e);
+            }
+        },
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn poll(filter: &mut ClipboardFilter, text: &str, now: Instant) -> Option<String> {
+        filter.poll(
+            Some(text.to_string()),
+            false,
+            now,
+            &ClipboardWatchConfig::default(),
+        )
+    }
+
+    #[test]
+    fn test_debounce_and_repeat() {
+        let start = Instant::now();
+        let after = |ms| start + Duration::from_millis(ms);
+        let mut filter = ClipboardFilter::default();
+        filter.reset(Some("old".to_string()));
+        assert_eq!(poll(&mut filter, "old", after(0)), None);
+
+        // 内容变化后需要保持不变超过防抖时间
+        assert_eq!(poll(&mut filter, "hel", after(100)), None);
+        assert_eq!(poll(&mut filter, "hello", after(400)), None);
+        assert_eq!(poll(&mut filter, "hello", after(800)), None);
+        assert_eq!(
+            poll(&mut filter, "hello", after(900)),
+            Some("hello".to_string())
+        );
+        // 相同的内容只翻译一次
+        assert_eq!(poll(&mut filter, "hello", after(2000)), None);
+    }
+
+    #[test]
+    fn test_skip_short_and_own_content() {
+        let now = Instant::now();
+        let config = ClipboardWatchConfig {
+            debounce_ms: 0,
+            ..Default::default()
+        };
+        let mut filter = ClipboardFilter::default();
+        assert_eq!(
+            filter.poll(Some(" a ".to_string()), false, now, &config),
+            None
+        );
+
+        filter.ignore("译文".to_string());
+        assert_eq!(
+            filter.poll(Some("译文".to_string()), false, now, &config),
+            None
+        );
+        assert_eq!(
+            filter.poll(Some("copied".to_string()), true, now, &config),
+            None
+        );
+        assert_eq!(filter.poll(None, false, now, &config), None);
+        assert_eq!(
+            filter.poll(Some("new text".to_string()), false, now, &config),
+            Some("new text".to_string())
+        );
+    }
+}
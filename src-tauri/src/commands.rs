@@ -1,110 +1,248 @@
+use chrono::{DateTime, Utc};
+use tauri::{AppHandle, Emitter, State};
 
+use crate::ai::models::{self, ModelDetails, ModelInfo};
+use crate::ai::{CompareResult, DictionaryResult, SavedTranslation, TranslationContext};
+use crate::app_setup;
+#[cfg(desktop)]
+use crate::app_window::{self, AppWindow};
+use crate::config::{AppConfig, LangPair, ProviderProfile};
+use crate::dictionary::{self, DictionaryDefinition, DictionaryInfo};
+use crate::stats::{self, StatsGroupBy, UsageStat};
+use crate::{ai, database::TranslationRecord, lang, resp::R, state::AppState, tray_menu};
 
-use tauri::path::BaseDirectory;
-use tauri::Manager;
-
-use crate::config::{get_database, AppConfig};
-use crate::{
-    ai, config,
-    database::{TranslationRecord},
-    lang,
-    resp::R,
-};
-use std::fs;
+/// 翻译，`context` 可提供前后文或领域提示
+#[tauri::command]
+pub async fn translate(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    text: &str,
+    target_lang: lang::Lang,
+    source_lang: lang::Lang,
+    context: Option<TranslationContext>,
+) -> Result<R<SavedTranslation>, R<String>> {
+    println!("开始调用tauri::command translate: {:?}", text);
 
+    match ai::translate(&state, text.to_string(), target_lang, source_lang, context).await {
+        Ok(translation) => {
+            tray_menu::refresh(&app);
+            Ok(R::success(translation))
+        }
+        Err(e) => Err(R::fail(1, &format!("{}", e))),
+    }
+}
 
+/// 词典模式翻译，单词和短语会同时返回候选译文、词性、发音和例句
+#[tauri::command]
+pub async fn translate_dictionary(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    text: &str,
+    target_lang: lang::Lang,
+    source_lang: lang::Lang,
+) -> Result<R<DictionaryResult>, R<String>> {
+    match ai::translate_dictionary(&state, text.to_string(), target_lang, source_lang).await {
+        Ok(result) => {
+            tray_menu::refresh(&app);
+            Ok(R::success(result))
+        }
+        Err(e) => Err(R::fail(1, &format!("{}", e))),
+    }
+}
 
+/// 按调整要求修改译文，`record_id` 为翻译或上次调整返回的记录 ID
+#[tauri::command]
+pub async fn refine_translation(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    record_id: &str,
+    instruction: &str,
+) -> Result<R<TranslationRecord>, R<String>> {
+    match ai::refine_translation(&state, record_id, instruction).await {
+        Ok(record) => {
+            tray_menu::refresh(&app);
+            Ok(R::success(record))
+        }
+        Err(e) => Err(R::fail(1, &format!("调整译文失败: {}", e))),
+    }
+}
 
+/// 使用多个翻译服务同时翻译同一段文本，`profiles` 为空时使用全部已配置的服务
+#[tauri::command]
+pub async fn translate_compare(
+    state: State<'_, AppState>,
+    text: &str,
+    target_lang: lang::Lang,
+    source_lang: lang::Lang,
+    profiles: Option<Vec<String>>,
+) -> Result<R<Vec<CompareResult>>, R<String>> {
+    let config = state.config();
+    let mut selected = config.all_profiles();
+    if let Some(names) = profiles.filter(|names| !names.is_empty()) {
+        if let Some(missing) = names
+            .iter()
+            .find(|name| !selected.iter().any(|p| &p.name == *name))
+        {
+            return Err(R::fail(1, &format!("未找到翻译服务配置: {}", missing)));
+        }
+        selected.retain(|p| names.contains(&p.name));
+    }
 
+    let results = ai::translate_compare(&config, selected, text, target_lang, source_lang).await;
+    Ok(R::success(results))
+}
 
-/// 翻译
+/// 将对比翻译中选中的结果保存到历史记录
 #[tauri::command]
-pub async fn translate(
+pub async fn save_compare_result(
+    app: AppHandle,
+    state: State<'_, AppState>,
     text: &str,
     target_lang: lang::Lang,
     source_lang: lang::Lang,
-) -> Result<R<String>, R<String>> {
-    println!("开始调用tauri::command translate: {:?}", text);
-    
-    match ai::translate(text.to_string(), target_lang, source_lang).await {
-        Ok(translated_text) => Ok(R::success(translated_text)),
-        Err(e) => Err(R::fail(1, &format!("{}", e))),
+    result: CompareResult,
+) -> Result<R<String>, String> {
+    let translated_text = result
+        .text
+        .ok_or_else(|| format!("翻译服务 {} 没有可保存的结果", result.profile))?;
+    let database = state.command_database().await?;
+
+    match database
+        .save_translation(
+            text,
+            &translated_text,
+            source_lang.into(),
+            target_lang.into(),
+            &result.meta,
+        )
+        .await
+    {
+        Ok(id) => {
+            tray_menu::refresh(&app);
+            Ok(R::success(id))
+        }
+        Err(e) => Err(format!("保存翻译记录失败: {}", e)),
     }
 }
 
-/// 关闭窗口
+/// 关闭窗口，主窗口只隐藏，独立窗口关闭时保存位置和大小
 #[tauri::command]
 pub async fn close_window(webview_window: tauri::WebviewWindow) {
     println!("WebviewWindow: {}", webview_window.label());
-    // 关闭webview_window
-    webview_window.hide().unwrap();
+    if webview_window.label() == "main" {
+        app_setup::hide_main_window(&webview_window);
+    } else if let Err(e) = webview_window.close() {
+        eprintln!("关闭窗口失败: {}", e);
+    }
+}
+
+/// 固定或取消固定主窗口，固定后失去焦点时不隐藏，并可以调整大小
+#[cfg(desktop)]
+#[tauri::command]
+pub fn set_pinned(app: AppHandle, pinned: bool) -> Result<R<()>, R<String>> {
+    match app_setup::set_pinned(&app, pinned) {
+        Ok(()) => Ok(R::success(())),
+        Err(e) => Err(R::fail(1, &format!("固定窗口失败: {}", e))),
+    }
+}
+
+/// 打开历史或设置窗口，已打开时聚焦
+#[cfg(desktop)]
+#[tauri::command]
+pub fn open_window(app: AppHandle, window: AppWindow) -> Result<R<()>, R<String>> {
+    match app_window::open(&app, window) {
+        Ok(()) => Ok(R::success(())),
+        Err(e) => Err(R::fail(1, &format!("打开窗口失败: {}", e))),
+    }
 }
 
 /// 加载配置
 #[tauri::command]
-pub fn load_config() -> Result<R<AppConfig>, R<String>> {
+pub fn load_config(state: State<'_, AppState>) -> Result<R<AppConfig>, R<String>> {
     // 获取配置
-    let confg = config::get_config();
+    let confg = state.config();
     println!("配置文件: {:?}", confg);
-    match confg {
-        Ok(confg) => Ok(R::success(confg)),
-        Err(_) => Err(R::fail(1, "获取配置失败")),
-    }
+    Ok(R::success(confg))
 }
 
-/// 保存配置
+/// 保存配置，全局快捷键有变化时重新注册
 #[tauri::command]
 pub fn update_config(
-    app_handle: tauri::AppHandle,
-    new_config: AppConfig,
+    app: AppHandle,
+    state: State<'_, AppState>,
+    mut new_config: AppConfig,
 ) -> Result<R<()>, R<String>> {
-    let config_path = app_handle
-        .path()
-        .resolve(config::CONFIG_PATH, BaseDirectory::Resource)
-        .expect("Failed to resolve resource path");
-    let config_str = serde_json::to_string_pretty(&new_config).expect("Failed to serialize config");
-
-    fs::write(config_path, config_str).expect("Failed to write config file");
-    println!("配置文件已更新: {:?}", new_config);
-    // 更新config：：CONFIG
-    {
-        let mut config_guard = config::CONFIG
-            .get()
-            .expect("Config not initialized")
-            .lock()
-            .expect("Mutex poisoned");
-        *config_guard = new_config;
-    }
-    // 再次读取以验证修改
+    println!("更新配置文件: {:?}", new_config);
+    new_config
+        .provider
+        .reset_thinking_on_platform_change(&state.config().provider);
+    match apply_config(&app, &state, new_config) {
+        Ok(()) => Ok(R::success(())),
+        Err(e) => Err(R::fail(1, &format!("保存配置失败: {}", e))),
+    }
+}
+
+// 保存配置，然后刷新托盘菜单并通知所有窗口配置已更新
+fn apply_config(app: &AppHandle, state: &AppState, new_config: AppConfig) -> anyhow::Result<()> {
+    save_config(app, state, new_config)?;
+    // 托盘菜单显示语言方向、翻译服务等配置，其他窗口需要更新主题
+    tray_menu::refresh(app);
+    if let Err(e) = app.emit("config-updated", ()) {
+        eprintln!("Failed to emit event: {}", e);
+    }
+    Ok(())
+}
+
+// 先注册新的快捷键再保存配置，任一步骤失败时恢复原有快捷键
+fn save_config(app: &AppHandle, state: &AppState, new_config: AppConfig) -> anyhow::Result<()> {
+    #[cfg(desktop)]
     {
-        let config_guard = config::CONFIG.get().unwrap().lock().unwrap();
-        println!("Verified config: {:?}", *config_guard);
+        let old_shortcuts = state.config().shortcuts;
+        if new_config.shortcuts != old_shortcuts {
+            let restore = |e: anyhow::Error| {
+                if let Err(e) = app_setup::register_shortcuts(app, &old_shortcuts) {
+                    eprintln!("恢复快捷键失败: {}", e);
+                }
+                e
+            };
+            app_setup::register_shortcuts(app, &new_config.shortcuts).map_err(restore)?;
+            return state.update_config(new_config).map_err(restore);
+        }
     }
+    #[cfg(not(desktop))]
+    let _ = app;
+    state.update_config(new_config)
+}
 
-    Ok(R::success(()))
+/// 设置当前语言方向，前端切换语言时调用，快捷键和托盘菜单触发的翻译会使用该方向
+#[tauri::command]
+pub fn set_lang_pair(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    lang_pair: LangPair,
+) -> Result<R<()>, R<String>> {
+    let mut config = state.config();
+    if config.lang_pair == lang_pair {
+        return Ok(R::success(()));
+    }
+    config.lang_pair = lang_pair;
+    match state.update_config(config) {
+        Ok(()) => {
+            tray_menu::refresh(&app);
+            Ok(R::success(()))
+        }
+        Err(e) => Err(R::fail(1, &format!("保存语言方向失败: {}", e))),
+    }
 }
 
 /// 获取翻译历史记录
 #[tauri::command]
 pub async fn get_translation_history(
+    state: State<'_, AppState>,
     limit: Option<i32>,
     offset: Option<i32>,
 ) -> Result<R<Vec<TranslationRecord>>, String> {
-    let database = {
-        match get_database() {
-            Ok(db_arc) => match db_arc.lock() {
-                Ok(db_guard) => {
-                    if let Some(ref database) = *db_guard {
-                        database.clone()
-                    } else {
-                        return Err("数据库未初始化".to_string());
-                    }
-                }
-                Err(_) => return Err("数据库锁定失败".to_string()),
-            },
-            Err(e) => return Err(e),
-        }
-    };
+    let database = state.command_database().await?;
 
     match database.get_translation_history(limit, offset).await {
         Ok(records) => Ok(R::success(records)),
@@ -115,24 +253,11 @@ pub async fn get_translation_history(
 /// 搜索翻译记录
 #[tauri::command]
 pub async fn search_translations(
+    state: State<'_, AppState>,
     query: &str,
     limit: Option<i32>,
 ) -> Result<R<Vec<TranslationRecord>>, String> {
-    let database = {
-        match get_database() {
-            Ok(db_arc) => match db_arc.lock() {
-                Ok(db_guard) => {
-                    if let Some(ref database) = *db_guard {
-                        database.clone()
-                    } else {
-                        return Err("数据库未初始化".to_string());
-                    }
-                }
-                Err(_) => return Err("数据库锁定失败".to_string()),
-            },
-            Err(e) => return Err(e),
-        }
-    };
+    let database = state.command_database().await?;
 
     match database.search_translations(query, limit).await {
         Ok(records) => Ok(R::success(records)),
@@ -142,81 +267,184 @@ pub async fn search_translations(
 
 /// 删除翻译记录
 #[tauri::command]
-pub async fn delete_translation(id: &str) -> Result<R<bool>, String> {
-    let database = {
-        match get_database() {
-            Ok(db_arc) => match db_arc.lock() {
-                Ok(db_guard) => {
-                    if let Some(ref database) = *db_guard {
-                        database.clone()
-                    } else {
-                        return Err("数据库未初始化".to_string());
-                    }
-                }
-                Err(_) => return Err("数据库锁定失败".to_string()),
-            },
-            Err(e) => return Err(e),
-        }
-    };
+pub async fn delete_translation(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    id: &str,
+) -> Result<R<bool>, String> {
+    let database = state.command_database().await?;
 
     match database.delete_translation(id).await {
-        Ok(deleted) => Ok(R::success(deleted)),
+        Ok(deleted) => {
+            tray_menu::refresh(&app);
+            Ok(R::success(deleted))
+        }
         Err(e) => Err(format!("删除翻译记录失败: {}", e)),
     }
 }
 
+/// 导入离线词典，支持 StarDict（.ifo）、CSV 和 JSON 文件
+#[tauri::command]
+pub async fn import_dictionary(
+    state: State<'_, AppState>,
+    path: &str,
+) -> Result<R<DictionaryInfo>, String> {
+    let database = state.command_database().await?;
+
+    match dictionary::import(&database, std::path::Path::new(path)).await {
+        Ok(info) => Ok(R::success(info)),
+        Err(e) => Err(format!("导入词典失败: {}", e)),
+    }
+}
+
+/// 获取已导入的离线词典
+#[tauri::command]
+pub async fn list_dictionaries(
+    state: State<'_, AppState>,
+) -> Result<R<Vec<DictionaryInfo>>, String> {
+    let database = state.command_database().await?;
+
+    match database.list_dictionaries().await {
+        Ok(dictionaries) => Ok(R::success(dictionaries)),
+        Err(e) => Err(format!("获取词典列表失败: {}", e)),
+    }
+}
+
+/// 删除离线词典及其词条
+#[tauri::command]
+pub async fn remove_dictionary(state: State<'_, AppState>, id: i64) -> Result<R<bool>, String> {
+    let database = state.command_database().await?;
+
+    match database.remove_dictionary(id).await {
+        Ok(removed) => Ok(R::success(removed)),
+        Err(e) => Err(format!("删除词典失败: {}", e)),
+    }
+}
+
+/// 在离线词典中查询单词
+#[tauri::command]
+pub async fn lookup_dictionary(
+    state: State<'_, AppState>,
+    word: &str,
+) -> Result<R<Vec<DictionaryDefinition>>, String> {
+    let database = state.command_database().await?;
+
+    match database.lookup_word(word).await {
+        Ok(definitions) => Ok(R::success(definitions)),
+        Err(e) => Err(format!("查询词典失败: {}", e)),
+    }
+}
+
 /// 清空翻译历史
 #[tauri::command]
-pub async fn clear_translation_history() -> Result<R<u64>, String> {
-    let database = {
-        match get_database() {
-            Ok(db_arc) => match db_arc.lock() {
-                Ok(db_guard) => {
-                    if let Some(ref database) = *db_guard {
-                        database.clone()
-                    } else {
-                        return Err("数据库未初始化".to_string());
-                    }
-                }
-                Err(_) => return Err("数据库锁定失败".to_string()),
-            },
-            Err(e) => return Err(e),
-        }
-    };
+pub async fn clear_translation_history(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<R<u64>, String> {
+    let database = state.command_database().await?;
 
     match database.clear_history().await {
-        Ok(count) => Ok(R::success(count)),
+        Ok(count) => {
+            tray_menu::refresh(&app);
+            Ok(R::success(count))
+        }
         Err(e) => Err(format!("清空翻译历史失败: {}", e)),
     }
 }
 
+/// 获取按时间、平台、模型或语言对分组的翻译用量统计
 #[tauri::command]
-pub fn reset_config(app_handle: tauri::AppHandle) -> Result<R<()>, R<String>> {
-    let config_path = app_handle
-        .path()
-        .resolve(config::CONFIG_PATH, BaseDirectory::Resource)
-        .expect("Failed to resolve resource path");
+pub async fn get_usage_stats(
+    state: State<'_, AppState>,
+    group_by: StatsGroupBy,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+) -> Result<R<Vec<UsageStat>>, String> {
+    let database = state.command_database().await?;
 
-    // 重置配置
-    let config = AppConfig::default();
-    let config_str = serde_json::to_string_pretty(&config).expect("Failed to serialize config");
-    fs::write(config_path, config_str).expect("Failed to write config file");
-    println!("配置文件已重置: {:?}", config);
+    match database.usage_rows(group_by, start, end).await {
+        Ok(rows) => Ok(R::success(stats::aggregate(
+            rows,
+            &state.config().model_prices,
+        ))),
+        Err(e) => Err(format!("获取用量统计失败: {}", e)),
+    }
+}
 
-    // 更新config：：CONFIG
-    {
-        let mut config_guard = config::CONFIG
-            .get()
-            .expect("Config not initialized")
-            .lock()
-            .expect("Mutex poisoned");
-        *config_guard = config;
-    }
-    // 再次读取以验证修改
-    {
-        let config_guard = config::CONFIG.get().unwrap().lock().unwrap();
-        println!("Verified config: {:?}", *config_guard);
+/// 获取指定时间范围内的用量汇总
+#[tauri::command]
+pub async fn get_usage_summary(
+    state: State<'_, AppState>,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+) -> Result<R<UsageStat>, String> {
+    let database = state.command_database().await?;
+
+    match database.usage_rows(StatsGroupBy::Total, start, end).await {
+        Ok(rows) => {
+            let summary = stats::aggregate(rows, &state.config().model_prices)
+                .pop()
+                .unwrap_or_else(|| UsageStat {
+                    key: "total".to_string(),
+                    ..Default::default()
+                });
+            Ok(R::success(summary))
+        }
+        Err(e) => Err(format!("获取用量汇总失败: {}", e)),
     }
+}
+
+/// 获取翻译服务可用的模型列表
+///
+/// `profile` 由设置页面传入，未保存的接口地址也可以直接查询。
+#[tauri::command]
+pub async fn list_models(profile: ProviderProfile) -> Result<R<Vec<ModelInfo>>, R<String>> {
+    match models::list_models(&profile).await {
+        Ok(list) => Ok(R::success(list)),
+        Err(e) => Err(R::fail(1, &format!("获取模型列表失败: {}", e))),
+    }
+}
 
-    Ok(R::success(()))
+/// 获取 Ollama 模型详情，`name` 为空时使用配置中的模型
+#[tauri::command]
+pub async fn show_model(
+    profile: ProviderProfile,
+    name: Option<String>,
+) -> Result<R<ModelDetails>, R<String>> {
+    let name = name.unwrap_or_else(|| profile.model_name.clone());
+    match models::show_model(&profile, &name).await {
+        Ok(details) => Ok(R::success(details)),
+        Err(e) => Err(R::fail(1, &format!("获取模型详情失败: {}", e))),
+    }
+}
+
+/// 拉取 Ollama 模型，通过 `model-pull-progress` 事件推送下载进度
+#[tauri::command]
+pub async fn pull_model(
+    app: AppHandle,
+    profile: ProviderProfile,
+    name: Option<String>,
+) -> Result<R<()>, R<String>> {
+    let name = name.unwrap_or_else(|| profile.model_name.clone());
+    let result = models::pull_model(&profile, &name, |progress| {
+        app.emit("model-pull-progress", progress)
+            .unwrap_or_else(|e| eprintln!("Failed to emit event: {}", e));
+    })
+    .await;
+
+    match result {
+        Ok(()) => Ok(R::success(())),
+        Err(e) => Err(R::fail(1, &format!("{}", e))),
+    }
+}
+
+/// 重置配置
+#[tauri::command]
+pub fn reset_config(app: AppHandle, state: State<'_, AppState>) -> Result<R<()>, R<String>> {
+    let config = AppConfig::default();
+    println!("重置配置文件: {:?}", config);
+    match apply_config(&app, &state, config) {
+        Ok(()) => Ok(R::success(())),
+        Err(e) => Err(R::fail(1, &format!("重置配置失败: {}", e))),
+    }
 }
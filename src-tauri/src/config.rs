@@ -1,14 +1,10 @@
-use std::{
-    fs,
-    sync::{Arc, Mutex},
-};
+use std::{collections::HashMap, fs, path::Path};
 
-use anyhow::Error;
-use once_cell::sync::{Lazy, OnceCell};
+use anyhow::{anyhow, Error};
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use tauri::{path::BaseDirectory, App, Manager};
 
-use crate::database::Database;
+use crate::lang::Lang;
 
 pub const INIT_WEIDTH: f64 = 300.0;
 pub const INIT_HEIGHT: f64 = 350.0;
@@ -27,10 +23,49 @@ pub enum PlatformType {
     DeepSeek,
     ChatGPT,
     MTranServer,
+    Anthropic,
+    Gemini,
+    DeepL,
+    LibreTranslate,
+    RestApi,
+    OpenAICompatible,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct AppConfig {
+impl PlatformType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PlatformType::OLLama => "OLLama",
+            PlatformType::DeepSeek => "DeepSeek",
+            PlatformType::ChatGPT => "ChatGPT",
+            PlatformType::MTranServer => "MTranServer",
+            PlatformType::Anthropic => "Anthropic",
+            PlatformType::Gemini => "Gemini",
+            PlatformType::DeepL => "DeepL",
+            PlatformType::LibreTranslate => "LibreTranslate",
+            PlatformType::RestApi => "RestApi",
+            PlatformType::OpenAICompatible => "OpenAICompatible",
+        }
+    }
+
+    /// 是否为使用模型和提示词的大模型平台，机器翻译引擎返回 false
+    pub fn uses_prompt(&self) -> bool {
+        !matches!(
+            self,
+            PlatformType::MTranServer
+                | PlatformType::DeepL
+                | PlatformType::LibreTranslate
+                | PlatformType::RestApi
+        )
+    }
+}
+
+pub const DEFAULT_PROFILE_NAME: &str = "default";
+
+/// 翻译服务配置
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ProviderProfile {
+    #[serde(default = "default_profile_name")]
+    pub name: String,
     #[serde(rename = "apiKey")]
     pub api_key: String,
     #[serde(rename = "apiUrl")]
@@ -38,100 +73,715 @@ pub struct AppConfig {
     pub platform: PlatformType,
     #[serde(rename = "modelName")]
     pub model_name: String,
+    /// 使用流式响应，目前仅 Anthropic 支持
+    #[serde(default)]
+    pub stream: bool,
+    /// DeepL 译文的正式程度，如 more、less、prefer_more、prefer_less
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub formality: Option<String>,
+    /// DeepL 术语表 ID，使用术语表时必须指定源语言
+    #[serde(
+        rename = "glossaryId",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub glossary_id: Option<String>,
+    /// 通用 REST 翻译接口配置，仅 RestApi 平台使用
+    #[serde(rename = "restApi", default, skip_serializing_if = "Option::is_none")]
+    pub rest_api: Option<RestApiOptions>,
+    /// OpenAI 兼容接口的认证方式和附加参数，仅 OpenAICompatible 平台使用
+    #[serde(
+        rename = "openaiCompatible",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub openai_compatible: Option<OpenAICompatibleOptions>,
+    /// 采样参数，未设置的参数不会发送
+    #[serde(default, skip_serializing_if = "SamplingOptions::is_empty")]
+    pub sampling: SamplingOptions,
+    /// 思考（推理）模式的控制方式，未设置时使用平台的默认值，见 [`ProviderProfile::thinking_mode`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thinking: Option<ThinkingMode>,
+    /// Ollama 的模型加载和上下文参数，仅 OLLama 平台使用
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ollama: Option<OllamaOptions>,
+}
+
+impl Default for ProviderProfile {
+    fn default() -> Self {
+        Self {
+            name: default_profile_name(),
+            api_key: "key".to_string(),
+            api_url: "http://localhost:11434/api/chat".to_string(),
+            platform: PlatformType::OLLama,
+            model_name: "qwen3:1.7b".to_string(),
+            stream: false,
+            formality: None,
+            glossary_id: None,
+            rest_api: None,
+            openai_compatible: None,
+            sampling: SamplingOptions::default(),
+            thinking: None,
+            ollama: None,
+        }
+    }
+}
+
+impl ProviderProfile {
+    /// 实际使用的思考模式，配置中没有 `thinking` 时使用平台的默认值
+    ///
+    /// 早期的配置文件没有该字段，Ollama 上仍按原来的方式关闭思考。
+    pub fn thinking_mode(&self) -> ThinkingMode {
+        self.thinking
+            .unwrap_or_else(|| ThinkingMode::default_for(&self.platform))
+    }
+
+    /// 平台变化且思考模式未修改时，思考模式恢复为新平台的默认值
+    ///
+    /// 设置页没有思考模式选项，切换平台后沿用原平台的设置可能使请求被新平台拒绝。
+    pub fn reset_thinking_on_platform_change(&mut self, previous: &ProviderProfile) {
+        if self.platform != previous.platform && self.thinking == previous.thinking {
+            self.thinking = None;
+        }
+    }
+}
+
+fn default_profile_name() -> String {
+    DEFAULT_PROFILE_NAME.to_string()
+}
+
+/// 通用 REST 翻译接口配置
+///
+/// `headers`、`query` 和 `body` 中可以使用 `{{text}}`、`{{from}}`、`{{to}}` 和 `{{key}}` 占位符，
+/// `body` 中的替换值会按 JSON 字符串转义。
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct RestApiOptions {
+    /// 请求方法，默认为 POST
+    #[serde(default)]
+    pub method: String,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    #[serde(default)]
+    pub query: HashMap<String, String>,
+    /// JSON 请求体模板，为空时不发送请求体
+    #[serde(default)]
+    pub body: Option<String>,
+    /// 译文在响应 JSON 中的路径，以点分隔，数组使用下标，如 `data.translations.0.translatedText`
+    #[serde(rename = "resultPath")]
+    pub result_path: String,
+    /// 覆盖默认语言代码，如 `{"zh": "zh-CN"}`
+    #[serde(rename = "langCodes", default)]
+    pub lang_codes: HashMap<String, String>,
+}
+
+/// 采样参数，按平台转换为对应的请求字段
+///
+/// Ollama 放在 `options` 中（`maxTokens` 对应 `num_predict`），Gemini 放在 `generationConfig` 中，
+/// Anthropic 不支持 `seed`。
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct SamplingOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    #[serde(rename = "topP", skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f64>,
+    #[serde(rename = "maxTokens", skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<i64>,
+    /// OpenAI 推理模型的 reasoning_effort，如 minimal、low、medium、high
+    #[serde(rename = "reasoningEffort", skip_serializing_if = "Option::is_none")]
+    pub reasoning_effort: Option<String>,
+}
+
+impl SamplingOptions {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// 思考（推理）模式的控制方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum ThinkingMode {
+    /// 使用模型的默认行为
+    #[default]
+    Default,
+    /// 通过平台参数关闭思考：Ollama 发送 `think: false`，Gemini 将 `thinkingBudget` 设为 0；
+    /// OpenAI 格式的接口没有通用的关闭方式，需要时在 `sampling` 中设置 `reasoningEffort`
+    Disabled,
+    /// 在提示词末尾追加 ` /no_think`，适用于 Qwen3 等支持该指令的模型
+    NoThinkSuffix,
+}
+
+impl ThinkingMode {
+    /// 平台的默认思考模式，Ollama 的默认模型 qwen3 会输出思考过程，关闭以加快翻译
+    pub fn default_for(platform: &PlatformType) -> Self {
+        match platform {
+            PlatformType::OLLama => ThinkingMode::Disabled,
+            _ => ThinkingMode::Default,
+        }
+    }
+}
+
+/// Ollama 专用参数
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct OllamaOptions {
+    /// 模型在内存中保留的时间，如 `"30m"`，`-1` 表示常驻内存
+    #[serde(rename = "keepAlive", skip_serializing_if = "Option::is_none")]
+    pub keep_alive: Option<serde_json::Value>,
+    /// 上下文窗口大小，与预热时不一致会导致模型重新加载
+    #[serde(rename = "numCtx", skip_serializing_if = "Option::is_none")]
+    pub num_ctx: Option<u32>,
+    /// 最大生成 token 数，未设置时使用 `sampling.maxTokens`
+    #[serde(rename = "numPredict", skip_serializing_if = "Option::is_none")]
+    pub num_predict: Option<u32>,
+    /// 启动时预加载模型
+    #[serde(rename = "warmUp")]
+    pub warm_up: bool,
+}
+
+impl Default for OllamaOptions {
+    fn default() -> Self {
+        Self {
+            // Ollama 默认只保留 5 分钟，划词翻译的间隔通常更长
+            keep_alive: Some(serde_json::Value::from("30m")),
+            num_ctx: None,
+            num_predict: None,
+            warm_up: true,
+        }
+    }
+}
+
+/// OpenAI 兼容接口配置，适用于 Azure OpenAI、OpenRouter、LM Studio、vLLM 等服务
+///
+/// 例如 Azure OpenAI 使用 `api-key` 请求头和 `api-version` 查询参数：
+/// `authHeader` 设为 `api-key`，`authFormat` 设为 `{{key}}`。
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct OpenAICompatibleOptions {
+    /// 认证请求头名称，为空时不发送认证头
+    #[serde(rename = "authHeader")]
+    pub auth_header: String,
+    /// 认证请求头的值，`{{key}}` 会替换为 API Key
+    #[serde(rename = "authFormat")]
+    pub auth_format: String,
+    pub headers: HashMap<String, String>,
+    pub query: HashMap<String, String>,
+    /// 合并到请求体中的附加字段，如 temperature、top_p、max_tokens
+    #[serde(rename = "extraBody")]
+    pub extra_body: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Default for OpenAICompatibleOptions {
+    fn default() -> Self {
+        Self {
+            auth_header: "Authorization".to_string(),
+            auth_format: "Bearer {{key}}".to_string(),
+            headers: HashMap::new(),
+            query: HashMap::new(),
+            extra_body: serde_json::Map::new(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AppConfig {
+    /// 当前使用的翻译服务，字段直接展开在配置顶层
+    #[serde(flatten)]
+    pub provider: ProviderProfile,
     pub theme: ThemeType,
     pub prompt: String,
     #[serde(rename = "systemPrompt")]
     pub system_prompt: String,
+    /// 按模型名称配置的价格，用于估算翻译费用
+    #[serde(rename = "modelPrices", default)]
+    pub model_prices: HashMap<String, ModelPrice>,
+    /// 额外保存的翻译服务，可用于对比翻译
+    #[serde(default)]
+    pub profiles: Vec<ProviderProfile>,
+    /// 作为示例发送给模型的最近翻译条数，0 表示不使用历史翻译
+    #[serde(rename = "contextHistory", default)]
+    pub context_history: u32,
+    /// 全局快捷键
+    #[serde(default)]
+    pub shortcuts: ShortcutConfig,
+    /// 当前语言方向，快捷键和托盘菜单触发的翻译使用
+    #[serde(rename = "langPair", default)]
+    pub lang_pair: LangPair,
+    /// 可以通过快捷键依次切换的预设语言方向
+    #[serde(rename = "langPairs", default = "default_lang_pairs")]
+    pub lang_pairs: Vec<LangPair>,
+    /// 快捷键读取选中文本的方式
+    #[serde(default)]
+    pub selection: SelectionConfig,
+    /// 监听剪贴板，复制文本后自动翻译
+    #[serde(rename = "clipboardWatch", default)]
+    pub clipboard_watch: ClipboardWatchConfig,
+    /// 不打开翻译窗口的操作通过系统通知显示结果和错误
+    #[serde(default)]
+    pub notification: NotificationConfig,
+    /// 主窗口失去焦点时自动隐藏
+    #[serde(rename = "autoHide", default = "default_auto_hide")]
+    pub auto_hide: bool,
+    /// 历史、设置等独立窗口关闭时的位置和大小，键为窗口标签
+    #[serde(default)]
+    pub windows: HashMap<String, WindowGeometry>,
+    /// 翻译窗口的显示位置
+    #[serde(rename = "popupPlacement", default)]
+    pub popup_placement: PopupPlacement,
+    /// 翻译窗口上次隐藏时的位置，`popupPlacement` 为 `LastPosition` 时使用
+    #[serde(
+        rename = "lastPopupPosition",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub last_popup_position: Option<ScreenPosition>,
+}
+
+impl AppConfig {
+    /// 当前翻译服务及所有保存的翻译服务，名称重复时只保留第一个
+    pub fn all_profiles(&self) -> Vec<ProviderProfile> {
+        let mut profiles = vec![self.provider.clone()];
+        for profile in &self.profiles {
+            if !profiles.iter().any(|p| p.name == profile.name) {
+                profiles.push(profile.clone());
+            }
+        }
+        profiles
+    }
+
+    /// 预设语言方向中当前方向的下一个，当前方向不在预设中时返回第一个
+    pub fn next_lang_pair(&self) -> LangPair {
+        let next = self
+            .lang_pairs
+            .iter()
+            .position(|pair| pair == &self.lang_pair)
+            .map_or(0, |index| index + 1);
+        self.lang_pairs
+            .get(next)
+            .or(self.lang_pairs.first())
+            .copied()
+            .unwrap_or(self.lang_pair)
+    }
+
+    /// 切换当前翻译服务，原来的翻译服务保存到 `profiles` 中
+    pub fn switch_profile(&mut self, name: &str) -> Result<(), Error> {
+        if self.provider.name == name {
+            return Ok(());
+        }
+        let index = self
+            .profiles
+            .iter()
+            .position(|profile| profile.name == name)
+            .ok_or_else(|| anyhow!("未找到翻译服务配置: {}", name))?;
+        let profile = self.profiles.remove(index);
+        let previous = std::mem::replace(&mut self.provider, profile);
+        // 手动编辑的配置中可能有与原翻译服务同名的配置，移除后下标可能超出长度
+        self.profiles
+            .retain(|profile| profile.name != previous.name);
+        let index = index.min(self.profiles.len());
+        self.profiles.insert(index, previous);
+        Ok(())
+    }
+
+    /// 移除与当前翻译服务或前面的配置同名的翻译服务，返回被移除的名称
+    pub fn remove_duplicate_profiles(&mut self) -> Vec<String> {
+        let mut names = vec![self.provider.name.clone()];
+        let mut removed = Vec::new();
+        self.profiles.retain(|profile| {
+            if names.contains(&profile.name) {
+                removed.push(profile.name.clone());
+                false
+            } else {
+                names.push(profile.name.clone());
+                true
+            }
+        });
+        removed
+    }
+}
+
+/// 模型价格，单位为每百万 token
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub struct ModelPrice {
+    #[serde(rename = "inputPerMillion", default)]
+    pub input_per_million: f64,
+    #[serde(rename = "outputPerMillion", default)]
+    pub output_per_million: f64,
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
-            api_key: "key".to_string(),
-            api_url: "http://localhost:11434/api/chat".to_string(),
-            platform: PlatformType::OLLama,
-            model_name: "qwen3:1.7b".to_string(),
+            provider: ProviderProfile::default(),
             theme: ThemeType::Dark,
             prompt: "Translate to {{to}} (output translation only):\n\n{{text}}".to_string(),
             system_prompt: "You are a professional {{to}} native translator who needs to fluently translate text into {{to}}.\n\n## Translation Rules\n1. Output only the translated content, without explanations or additional content (such as \"Here's the translation:\" or \"Translation as follows:\")\n2. The returned translation must maintain exactly the same number of paragraphs and format as the original text\n3. For content that should not be translated (such as proper nouns, code, etc.), keep the original text.\n".to_string(),
+            model_prices: HashMap::new(),
+            profiles: Vec::new(),
+            context_history: 0,
+            shortcuts: ShortcutConfig::default(),
+            lang_pair: LangPair::default(),
+            lang_pairs: default_lang_pairs(),
+            selection: SelectionConfig::default(),
+            clipboard_watch: ClipboardWatchConfig::default(),
+            notification: NotificationConfig::default(),
+            auto_hide: default_auto_hide(),
+            windows: HashMap::new(),
+            popup_placement: PopupPlacement::default(),
+            last_popup_position: None,
         }
     }
 }
 
-pub static CONFIG: OnceCell<Mutex<AppConfig>> = OnceCell::new();
-pub static REQUEST_CLIENT: Lazy<reqwest::Client> = Lazy::new(reqwest::Client::new);
-// 全局数据库实例
-pub static DATABASE: OnceCell<Arc<Mutex<Option<Database>>>> = OnceCell::new();
-
-// 初始化数据库
-pub async fn init_database(
-    app_handle: &tauri::AppHandle,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let db_path = app_handle
-        .path()
-        .resolve(DB_FILE_PATH, BaseDirectory::Resource)
-        .expect("Failed to resolve resource path");
-
-    let database = Database::new(db_path).await?;
-
-    DATABASE
-        .set(Arc::new(Mutex::new(Some(database))))
-        .map_err(|_| {
-            Box::new(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "Failed to initialize database",
-            )) as Box<dyn std::error::Error>
-        })?;
+fn default_auto_hide() -> bool {
+    true
+}
 
-    Ok(())
+/// 窗口的位置和大小，单位为逻辑像素
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct WindowGeometry {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// 翻译窗口的显示位置
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PopupPlacement {
+    /// 鼠标右下方，空间不足时显示在左侧或上方
+    #[default]
+    NearCursor,
+    /// 鼠标所在显示器的中央
+    Center,
+    /// 鼠标所在显示器的四个角
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    /// 上次隐藏时的位置，该位置不在任何显示器内时显示在鼠标附近
+    LastPosition,
+}
+
+/// 屏幕坐标，单位为物理像素，主显示器左侧或上方的显示器坐标为负数
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct ScreenPosition {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// 语言方向
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct LangPair {
+    pub source: Lang,
+    pub target: Lang,
+}
+
+impl Default for LangPair {
+    fn default() -> Self {
+        Self {
+            source: Lang::Auto,
+            target: Lang::Zh,
+        }
+    }
+}
+
+fn default_lang_pairs() -> Vec<LangPair> {
+    vec![
+        LangPair::default(),
+        LangPair {
+            source: Lang::Zh,
+            target: Lang::En,
+        },
+    ]
+}
+
+/// 读取选中文本的方式，都读取不到时使用剪贴板内容
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct SelectionConfig {
+    /// Linux 上读取 X11 PRIMARY 选区，选中文本后无需复制
+    #[serde(rename = "usePrimary")]
+    pub use_primary: bool,
+    /// 模拟复制快捷键读取选中的文本，读取后恢复原有的剪贴板内容
+    #[serde(rename = "simulateCopy")]
+    pub simulate_copy: bool,
+    /// 模拟复制后等待剪贴板更新的时间，单位为毫秒
+    #[serde(rename = "copyDelayMs")]
+    pub copy_delay_ms: u64,
+    /// 替换选中文本时，模拟粘贴后等待目标程序读取剪贴板的时间，之后恢复原有的剪贴板内容，单位为毫秒
+    #[serde(rename = "pasteDelayMs")]
+    pub paste_delay_ms: u64,
 }
 
-// 获取数据库实例的辅助函数
-pub fn get_database() -> Result<Arc<Mutex<Option<Database>>>, String> {
-    DATABASE
-        .get()
-        .ok_or_else(|| "Database not initialized".to_string())
-        .map(|db| db.clone())
+impl Default for SelectionConfig {
+    fn default() -> Self {
+        Self {
+            use_primary: true,
+            simulate_copy: false,
+            copy_delay_ms: 150,
+            paste_delay_ms: 300,
+        }
+    }
+}
+
+/// 剪贴板监听配置
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct ClipboardWatchConfig {
+    pub enabled: bool,
+    /// 少于该字符数的内容不翻译
+    #[serde(rename = "minLength")]
+    pub min_length: usize,
+    /// 剪贴板内容保持不变超过该时间后才翻译，单位为毫秒
+    #[serde(rename = "debounceMs")]
+    pub debounce_ms: u64,
+    /// 检查剪贴板的间隔，单位为毫秒
+    #[serde(rename = "pollIntervalMs")]
+    pub poll_interval_ms: u64,
+    /// 显示翻译结果的方式
+    pub output: WatchOutput,
+}
+
+impl Default for ClipboardWatchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_length: 2,
+            debounce_ms: 500,
+            poll_interval_ms: 500,
+            output: WatchOutput::Popup,
+        }
+    }
+}
+
+/// 系统通知配置
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct NotificationConfig {
+    /// 翻译并复制完成、快捷键操作失败时显示通知
+    pub enabled: bool,
+    /// 通知中译文的最大字符数，超过时截断
+    #[serde(rename = "maxLength")]
+    pub max_length: usize,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_length: 100,
+        }
+    }
 }
 
+/// 剪贴板监听的翻译结果显示方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum WatchOutput {
+    /// 在弹窗中翻译
+    #[default]
+    Popup,
+    /// 在后台翻译，通过系统通知显示译文
+    Notification,
+}
+
+/// 全局快捷键配置，值为快捷键字符串，如 `CmdOrCtrl+Alt+J`，为空表示不注册
+///
+/// `CmdOrCtrl` 在 macOS 上为 Command，其他系统上为 Ctrl。
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct ShortcutConfig {
+    /// 读取剪贴板并在弹窗中翻译
+    #[serde(rename = "showPopup")]
+    pub show_popup: String,
+    /// 翻译剪贴板内容并粘贴替换当前选中的文本
+    #[serde(rename = "translateReplace")]
+    pub translate_replace: String,
+    /// 翻译剪贴板内容并把译文写回剪贴板，不显示弹窗
+    #[serde(rename = "translateCopy")]
+    pub translate_copy: String,
+    /// 打开翻译历史
+    #[serde(rename = "openHistory")]
+    pub open_history: String,
+    /// 切换到下一个预设语言方向
+    #[serde(rename = "toggleLangPair")]
+    pub toggle_lang_pair: String,
+}
+
+impl Default for ShortcutConfig {
+    fn default() -> Self {
+        Self {
+            show_popup: "CmdOrCtrl+Alt+J".to_string(),
+            translate_replace: String::new(),
+            translate_copy: String::new(),
+            open_history: String::new(),
+            toggle_lang_pair: String::new(),
+        }
+    }
+}
+
+pub static REQUEST_CLIENT: Lazy<reqwest::Client> = Lazy::new(reqwest::Client::new);
+
 // 初始化配置
-pub fn init_config(app: &App) {
-    let config_path = app
-        .path()
-        .resolve(CONFIG_PATH, BaseDirectory::Resource)
-        .expect("Failed to resolve resource path");
+pub fn init_config(config_path: &Path) -> AppConfig {
     // 检查文件是否存在
     if !config_path.exists() {
         println!("配置文件不存在，使用默认配置");
         let default_config = AppConfig::default();
-        let config_str = serde_json::to_string_pretty(&default_config)
-            .expect("Failed to serialize default config");
-
-        fs::write(config_path, config_str).expect("Failed to write config file");
-
-        CONFIG
-            .set(Mutex::new(default_config))
-            .expect("Failed to set config");
+        save_config(config_path, &default_config).expect("Failed to write config file");
+        default_config
     } else {
-        let file = std::fs::File::open(&config_path).unwrap();
-        let config: AppConfig = serde_json::from_reader(file).unwrap();
-
-        CONFIG
-            .set(Mutex::new(config))
-            .expect("Failed to set config");
-        println!(
-            "读取配置文件成功，内容为：{:?}",
-            CONFIG.get().unwrap().lock().unwrap()
-        )
+        let file = std::fs::File::open(config_path).unwrap();
+        let mut config: AppConfig = serde_json::from_reader(file).unwrap();
+        println!("读取配置文件成功，内容为：{:?}", config);
+        // 翻译服务按名称切换，名称重复的配置无法选择
+        for name in config.remove_duplicate_profiles() {
+            eprintln!("忽略名称重复的翻译服务配置: {}", name);
+        }
+        config
     }
 }
 
-// 获取配置
-pub fn get_config() -> Result<AppConfig, Error> {
-    Ok(CONFIG
-        .get()
-        .expect("Config not initialized")
-        .lock()
-        .expect("Config lock failed")
-        .clone())
+// 保存配置到文件
+pub fn save_config(config_path: &Path, config: &AppConfig) -> Result<(), Error> {
+    let config_str = serde_json::to_string_pretty(config)?;
+    fs::write(config_path, config_str)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_lang_pair() {
+        let mut config = AppConfig::default();
+        assert_eq!(config.next_lang_pair(), config.lang_pairs[1]);
+        config.lang_pair = config.lang_pairs[1];
+        assert_eq!(config.next_lang_pair(), config.lang_pairs[0]);
+
+        // 当前方向不在预设中时从第一个开始
+        config.lang_pair = LangPair {
+            source: Lang::Ja,
+            target: Lang::Zh,
+        };
+        assert_eq!(config.next_lang_pair(), config.lang_pairs[0]);
+
+        config.lang_pairs.clear();
+        assert_eq!(config.next_lang_pair(), config.lang_pair);
+    }
+
+    #[test]
+    fn test_switch_profile() {
+        let profile = |name: &str| ProviderProfile {
+            name: name.to_string(),
+            ..Default::default()
+        };
+        let mut config = AppConfig {
+            profiles: vec![profile("deepl"), profile("openai")],
+            ..Default::default()
+        };
+        config.switch_profile("openai").unwrap();
+        assert_eq!(config.provider.name, "openai");
+        let names: Vec<_> = config.profiles.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["deepl", DEFAULT_PROFILE_NAME]);
+
+        config.switch_profile("openai").unwrap();
+        assert_eq!(config.provider.name, "openai");
+        assert!(config.switch_profile("gemini").is_err());
+        assert_eq!(config.all_profiles().len(), 3);
+    }
+
+    #[test]
+    fn test_switch_profile_with_duplicate_names() {
+        let profile = |name: &str| ProviderProfile {
+            name: name.to_string(),
+            ..Default::default()
+        };
+        // 未填写名称的配置与当前翻译服务同名
+        let mut config = AppConfig {
+            profiles: vec![profile(DEFAULT_PROFILE_NAME), profile("deepl")],
+            ..Default::default()
+        };
+        config.switch_profile("deepl").unwrap();
+        assert_eq!(config.provider.name, "deepl");
+        let names: Vec<_> = config.profiles.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, [DEFAULT_PROFILE_NAME]);
+
+        let mut config = AppConfig {
+            profiles: vec![
+                profile(DEFAULT_PROFILE_NAME),
+                profile("deepl"),
+                profile("deepl"),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            config.remove_duplicate_profiles(),
+            [DEFAULT_PROFILE_NAME, "deepl"]
+        );
+        assert_eq!(config.profiles.len(), 1);
+        assert_eq!(config.profiles[0].name, "deepl");
+    }
+
+    #[test]
+    fn test_reset_thinking_on_platform_change() {
+        let ollama = ProviderProfile {
+            thinking: Some(ThinkingMode::Disabled),
+            ..Default::default()
+        };
+
+        let mut openai = ProviderProfile {
+            platform: PlatformType::ChatGPT,
+            ..ollama.clone()
+        };
+        openai.reset_thinking_on_platform_change(&ollama);
+        assert_eq!(openai.thinking_mode(), ThinkingMode::Default);
+
+        // 同时修改了思考模式时保留新的设置
+        let mut gemini = ProviderProfile {
+            platform: PlatformType::Gemini,
+            thinking: Some(ThinkingMode::NoThinkSuffix),
+            ..ollama.clone()
+        };
+        gemini.reset_thinking_on_platform_change(&ollama);
+        assert_eq!(gemini.thinking_mode(), ThinkingMode::NoThinkSuffix);
+
+        let mut back = ProviderProfile {
+            platform: PlatformType::OLLama,
+            ..openai.clone()
+        };
+        back.reset_thinking_on_platform_change(&openai);
+        assert_eq!(back.thinking_mode(), ThinkingMode::Disabled);
+    }
+
+    #[test]
+    fn test_init_config_without_thinking() {
+        // 早期版本的配置文件，翻译服务的字段直接写在顶层
+        let config_path =
+            std::env::temp_dir().join(format!("tauri_yi_config_{}.json", uuid::Uuid::new_v4()));
+        let content = serde_json::json!({
+            "apiKey": "key",
+            "apiUrl": "http://localhost:11434/api/chat",
+            "platform": "OLLama",
+            "modelName": "qwen3:1.7b",
+            "theme": "Dark",
+            "prompt": "Translate to {{to}}:\n\n{{text}}",
+            "systemPrompt": "You are a translator."
+        });
+        fs::write(&config_path, content.to_string()).unwrap();
+
+        let config = init_config(&config_path);
+        fs::remove_file(&config_path).unwrap();
+        assert_eq!(config.provider.thinking, None);
+        assert_eq!(config.provider.thinking_mode(), ThinkingMode::Disabled);
+
+        let openai = ProviderProfile {
+            platform: PlatformType::ChatGPT,
+            ..config.provider
+        };
+        assert_eq!(openai.thinking_mode(), ThinkingMode::Default);
+    }
 }
@@ -1,10 +1,16 @@
-use sqlx::{sqlite::SqlitePool, Row};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::{
+    sqlite::{SqlitePool, SqliteRow},
+    Row,
+};
 use std::path::PathBuf;
 use std::sync::Arc;
 use uuid::Uuid;
 
+use crate::dictionary::{DictEntry, DictionaryDefinition, DictionaryInfo};
+use crate::stats::{StatsGroupBy, UsageRow};
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TranslationRecord {
     pub id: String,
@@ -12,9 +18,42 @@ pub struct TranslationRecord {
     pub translated_text: String,
     pub source_lang: String,
     pub target_lang: String,
+    #[serde(flatten)]
+    pub meta: TranslationMeta,
+    /// 修订记录对应的原始翻译记录 ID
+    pub parent_id: Option<String>,
+    /// 产生该修订的调整要求
+    pub instruction: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
+/// 翻译请求的附加信息，旧版本记录中这些字段为空
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TranslationMeta {
+    pub platform: Option<String>,
+    pub model_name: Option<String>,
+    pub prompt_hash: Option<String>,
+    pub duration_ms: Option<i64>,
+    pub prompt_tokens: Option<i64>,
+    pub completion_tokens: Option<i64>,
+}
+
+// 旧版本数据库中缺失、需要补充的列
+const META_COLUMNS: [(&str, &str); 8] = [
+    ("platform", "TEXT"),
+    ("model_name", "TEXT"),
+    ("prompt_hash", "TEXT"),
+    ("duration_ms", "INTEGER"),
+    ("prompt_tokens", "INTEGER"),
+    ("completion_tokens", "INTEGER"),
+    ("parent_id", "TEXT"),
+    ("instruction", "TEXT"),
+];
+
+const RECORD_COLUMNS: &str = "id, source_text, translated_text, source_lang, target_lang, \
+    platform, model_name, prompt_hash, duration_ms, prompt_tokens, completion_tokens, \
+    parent_id, instruction, created_at";
+
 #[derive(Clone)]
 pub struct Database {
     pool: Arc<SqlitePool>,
@@ -25,16 +64,16 @@ impl Database {
         // 确保数据库文件的目录存在
         if let Some(parent) = db_path.parent() {
             std::fs::create_dir_all(parent).map_err(|e| {
-                sqlx::Error::Io(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    format!("Failed to create database directory: {}", e),
-                ))
+                sqlx::Error::Io(std::io::Error::other(format!(
+                    "Failed to create database directory: {}",
+                    e
+                )))
             })?;
         }
 
         let database_url = format!("sqlite:{}", db_path.display());
         let pool = Arc::new(SqlitePool::connect(&database_url).await?);
-        
+
         let db = Database { pool };
         db.init_tables().await?;
         Ok(db)
@@ -49,6 +88,14 @@ impl Database {
                 translated_text TEXT NOT NULL,
                 source_lang TEXT NOT NULL,
                 target_lang TEXT NOT NULL,
+                platform TEXT,
+                model_name TEXT,
+                prompt_hash TEXT,
+                duration_ms INTEGER,
+                prompt_tokens INTEGER,
+                completion_tokens INTEGER,
+                parent_id TEXT,
+                instruction TEXT,
                 created_at TEXT NOT NULL
             )
             "#,
@@ -56,12 +103,39 @@ impl Database {
         .execute(&*self.pool)
         .await?;
 
+        self.migrate_meta_columns().await?;
+
+        // 离线词典
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS dictionaries (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE,
+                source TEXT NOT NULL,
+                word_count INTEGER NOT NULL,
+                created_at TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS dictionary_entries (
+                dictionary_id INTEGER NOT NULL,
+                word TEXT NOT NULL,
+                phonetic TEXT,
+                definition TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_dictionary_word
+                ON dictionary_entries(word COLLATE NOCASE);
+            "#,
+        )
+        .execute(&*self.pool)
+        .await?;
+
         // 创建索引以提高查询性能
         sqlx::query(
             r#"
             CREATE INDEX IF NOT EXISTS idx_created_at ON translation_history(created_at);
             CREATE INDEX IF NOT EXISTS idx_source_lang ON translation_history(source_lang);
             CREATE INDEX IF NOT EXISTS idx_target_lang ON translation_history(target_lang);
+            CREATE INDEX IF NOT EXISTS idx_model_name ON translation_history(model_name);
+            CREATE INDEX IF NOT EXISTS idx_parent_id ON translation_history(parent_id);
             "#,
         )
         .execute(&*self.pool)
@@ -70,20 +144,86 @@ impl Database {
         Ok(())
     }
 
+    // 为旧版本创建的表补充新增的列
+    async fn migrate_meta_columns(&self) -> Result<(), sqlx::Error> {
+        let existing: Vec<String> =
+            sqlx::query_scalar("SELECT name FROM pragma_table_info('translation_history')")
+                .fetch_all(&*self.pool)
+                .await?;
+
+        for (name, column_type) in META_COLUMNS {
+            if !existing.iter().any(|column| column == name) {
+                sqlx::query(&format!(
+                    "ALTER TABLE translation_history ADD COLUMN {} {}",
+                    name, column_type
+                ))
+                .execute(&*self.pool)
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+
     pub async fn save_translation(
         &self,
         source_text: &str,
         translated_text: &str,
         source_lang: &str,
         target_lang: &str,
+        meta: &TranslationMeta,
+    ) -> Result<String, sqlx::Error> {
+        self.insert_record(
+            source_text,
+            translated_text,
+            source_lang,
+            target_lang,
+            meta,
+            None,
+        )
+        .await
+    }
+
+    /// 保存对原始翻译的修订，原文和语言与原始记录相同
+    pub async fn save_revision(
+        &self,
+        original: &TranslationRecord,
+        instruction: &str,
+        translated_text: &str,
+        meta: &TranslationMeta,
+    ) -> Result<String, sqlx::Error> {
+        self.insert_record(
+            &original.source_text,
+            translated_text,
+            &original.source_lang,
+            &original.target_lang,
+            meta,
+            Some((&original.id, instruction)),
+        )
+        .await
+    }
+
+    // revision 为（原始记录 ID，调整要求）
+    async fn insert_record(
+        &self,
+        source_text: &str,
+        translated_text: &str,
+        source_lang: &str,
+        target_lang: &str,
+        meta: &TranslationMeta,
+        revision: Option<(&str, &str)>,
     ) -> Result<String, sqlx::Error> {
         let id = Uuid::new_v4().to_string();
         let created_at = Utc::now();
 
         sqlx::query(
             r#"
-            INSERT INTO translation_history (id, source_text, translated_text, source_lang, target_lang, created_at)
-            VALUES (?, ?, ?, ?, ?, ?)
+            INSERT INTO translation_history (
+                id, source_text, translated_text, source_lang, target_lang,
+                platform, model_name, prompt_hash, duration_ms, prompt_tokens, completion_tokens,
+                parent_id, instruction, created_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&id)
@@ -91,6 +231,14 @@ impl Database {
         .bind(translated_text)
         .bind(source_lang)
         .bind(target_lang)
+        .bind(&meta.platform)
+        .bind(&meta.model_name)
+        .bind(&meta.prompt_hash)
+        .bind(meta.duration_ms)
+        .bind(meta.prompt_tokens)
+        .bind(meta.completion_tokens)
+        .bind(revision.map(|(parent_id, _)| parent_id))
+        .bind(revision.map(|(_, instruction)| instruction))
         .bind(created_at.to_rfc3339())
         .execute(&*self.pool)
         .await?;
@@ -106,37 +254,21 @@ impl Database {
         let limit = limit.unwrap_or(50);
         let offset = offset.unwrap_or(0);
 
-        let rows = sqlx::query(
+        let rows = sqlx::query(&format!(
             r#"
-            SELECT id, source_text, translated_text, source_lang, target_lang, created_at
+            SELECT {}
             FROM translation_history
             ORDER BY created_at DESC
             LIMIT ? OFFSET ?
             "#,
-        )
+            RECORD_COLUMNS
+        ))
         .bind(limit)
         .bind(offset)
         .fetch_all(&*self.pool)
         .await?;
 
-        let mut records = Vec::new();
-        for row in rows {
-            let created_at_str: String = row.get("created_at");
-            let created_at = DateTime::parse_from_rfc3339(&created_at_str)
-                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?
-                .with_timezone(&Utc);
-
-            records.push(TranslationRecord {
-                id: row.get("id"),
-                source_text: row.get("source_text"),
-                translated_text: row.get("translated_text"),
-                source_lang: row.get("source_lang"),
-                target_lang: row.get("target_lang"),
-                created_at,
-            });
-        }
-
-        Ok(records)
+        rows.iter().map(row_to_record).collect()
     }
 
     pub async fn search_translations(
@@ -147,45 +279,100 @@ impl Database {
         let limit = limit.unwrap_or(50);
         let search_pattern = format!("%{}%", query);
 
-        let rows = sqlx::query(
+        let rows = sqlx::query(&format!(
             r#"
-            SELECT id, source_text, translated_text, source_lang, target_lang, created_at
+            SELECT {}
             FROM translation_history
-            WHERE source_text LIKE ? OR translated_text LIKE ?
+            WHERE source_text LIKE ?1 OR translated_text LIKE ?1
+                OR platform LIKE ?1 OR model_name LIKE ?1
             ORDER BY created_at DESC
-            LIMIT ?
+            LIMIT ?2
             "#,
-        )
-        .bind(&search_pattern)
+            RECORD_COLUMNS
+        ))
         .bind(&search_pattern)
         .bind(limit)
         .fetch_all(&*self.pool)
         .await?;
 
-        let mut records = Vec::new();
-        for row in rows {
-            let created_at_str: String = row.get("created_at");
-            let created_at = DateTime::parse_from_rfc3339(&created_at_str)
-                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?
-                .with_timezone(&Utc);
-
-            records.push(TranslationRecord {
-                id: row.get("id"),
-                source_text: row.get("source_text"),
-                translated_text: row.get("translated_text"),
-                source_lang: row.get("source_lang"),
-                target_lang: row.get("target_lang"),
-                created_at,
-            });
-        }
+        rows.iter().map(row_to_record).collect()
+    }
 
+    pub async fn get_translation(
+        &self,
+        id: &str,
+    ) -> Result<Option<TranslationRecord>, sqlx::Error> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM translation_history WHERE id = ?",
+            RECORD_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        row.as_ref().map(row_to_record).transpose()
+    }
+
+    /// 获取原始翻译的全部修订，按时间先后排列
+    pub async fn get_revisions(
+        &self,
+        parent_id: &str,
+    ) -> Result<Vec<TranslationRecord>, sqlx::Error> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM translation_history
+            WHERE parent_id = ?
+            ORDER BY created_at ASC
+            "#,
+            RECORD_COLUMNS
+        ))
+        .bind(parent_id)
+        .fetch_all(&*self.pool)
+        .await?;
+
+        rows.iter().map(row_to_record).collect()
+    }
+
+    /// 获取 `since` 之后指定语言方向的最近几条翻译，按时间先后排列，不包含修订记录
+    pub async fn recent_translations(
+        &self,
+        since: DateTime<Utc>,
+        source_lang: &str,
+        target_lang: &str,
+        limit: u32,
+    ) -> Result<Vec<TranslationRecord>, sqlx::Error> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM translation_history
+            WHERE created_at >= ?1 AND source_lang = ?2 AND target_lang = ?3
+                AND parent_id IS NULL
+            ORDER BY created_at DESC
+            LIMIT ?4
+            "#,
+            RECORD_COLUMNS
+        ))
+        .bind(since.to_rfc3339())
+        .bind(source_lang)
+        .bind(target_lang)
+        .bind(limit)
+        .fetch_all(&*self.pool)
+        .await?;
+
+        let mut records = rows
+            .iter()
+            .map(row_to_record)
+            .collect::<Result<Vec<_>, _>>()?;
+        records.reverse();
         Ok(records)
     }
 
+    // 删除原始翻译时一并删除它的修订
     pub async fn delete_translation(&self, id: &str) -> Result<bool, sqlx::Error> {
         let result = sqlx::query(
             r#"
-            DELETE FROM translation_history WHERE id = ?
+            DELETE FROM translation_history WHERE id = ?1 OR parent_id = ?1
             "#,
         )
         .bind(id)
@@ -206,4 +393,383 @@ impl Database {
 
         Ok(result.rows_affected())
     }
-}
\ No newline at end of file
+
+    /// 导入词典，同名词典的原有词条会被替换
+    pub async fn import_dictionary(
+        &self,
+        name: &str,
+        source: &str,
+        entries: &[DictEntry],
+    ) -> Result<DictionaryInfo, sqlx::Error> {
+        let created_at = Utc::now();
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM dictionary_entries
+            WHERE dictionary_id IN (SELECT id FROM dictionaries WHERE name = ?1);
+            DELETE FROM dictionaries WHERE name = ?1;
+            "#,
+        )
+        .bind(name)
+        .execute(&mut *tx)
+        .await?;
+
+        let id = sqlx::query(
+            r#"
+            INSERT INTO dictionaries (name, source, word_count, created_at)
+            VALUES (?, ?, ?, ?)
+            "#,
+        )
+        .bind(name)
+        .bind(source)
+        .bind(entries.len() as i64)
+        .bind(created_at.to_rfc3339())
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+
+        for entry in entries {
+            sqlx::query(
+                r#"
+                INSERT INTO dictionary_entries (dictionary_id, word, phonetic, definition)
+                VALUES (?, ?, ?, ?)
+                "#,
+            )
+            .bind(id)
+            .bind(&entry.word)
+            .bind(&entry.phonetic)
+            .bind(&entry.definition)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(DictionaryInfo {
+            id,
+            name: name.to_string(),
+            source: source.to_string(),
+            word_count: entries.len() as i64,
+            created_at,
+        })
+    }
+
+    pub async fn list_dictionaries(&self) -> Result<Vec<DictionaryInfo>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, name, source, word_count, created_at
+            FROM dictionaries
+            ORDER BY id
+            "#,
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(DictionaryInfo {
+                    id: row.get("id"),
+                    name: row.get("name"),
+                    source: row.get("source"),
+                    word_count: row.get("word_count"),
+                    created_at: parse_time(row.get("created_at"))?,
+                })
+            })
+            .collect()
+    }
+
+    pub async fn remove_dictionary(&self, id: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM dictionary_entries WHERE dictionary_id = ?1;
+            DELETE FROM dictionaries WHERE id = ?1;
+            "#,
+        )
+        .bind(id)
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 在所有离线词典中查询单词，不区分大小写
+    pub async fn lookup_word(&self, word: &str) -> Result<Vec<DictionaryDefinition>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT d.name AS dictionary, e.word, e.phonetic, e.definition
+            FROM dictionary_entries e
+            JOIN dictionaries d ON d.id = e.dictionary_id
+            WHERE e.word = ? COLLATE NOCASE
+            ORDER BY d.id
+            "#,
+        )
+        .bind(word.trim())
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DictionaryDefinition {
+                dictionary: row.get("dictionary"),
+                word: row.get("word"),
+                phonetic: row.get("phonetic"),
+                definition: row.get("definition"),
+            })
+            .collect())
+    }
+
+    /// 按分组统计翻译用量
+    ///
+    /// 结果按分组键、平台和模型细分，以便按模型价格估算费用。翻译次数和字符数只统计原始翻译，
+    /// 修订的原文与原始翻译相同；token 用量和耗时包含修订。按天、周、月分组时使用 UTC 日期。
+    pub async fn usage_rows(
+        &self,
+        group_by: StatsGroupBy,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<Vec<UsageRow>, sqlx::Error> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {} AS bucket,
+                platform,
+                model_name,
+                COALESCE(SUM(parent_id IS NULL), 0) AS translations,
+                COALESCE(SUM(CASE WHEN parent_id IS NULL THEN LENGTH(source_text) END), 0)
+                    AS characters,
+                COALESCE(SUM(prompt_tokens), 0) AS prompt_tokens,
+                COALESCE(SUM(completion_tokens), 0) AS completion_tokens,
+                COALESCE(SUM(duration_ms), 0) AS duration_sum_ms,
+                COUNT(duration_ms) AS duration_count
+            FROM translation_history
+            WHERE (?1 IS NULL OR created_at >= ?1) AND (?2 IS NULL OR created_at < ?2)
+            GROUP BY bucket, platform, model_name
+            ORDER BY bucket
+            "#,
+            group_by.bucket_expr()
+        ))
+        .bind(start.map(|start| start.to_rfc3339()))
+        .bind(end.map(|end| end.to_rfc3339()))
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| UsageRow {
+                bucket: row.get("bucket"),
+                platform: row.get("platform"),
+                model_name: row.get("model_name"),
+                translations: row.get("translations"),
+                characters: row.get("characters"),
+                prompt_tokens: row.get("prompt_tokens"),
+                completion_tokens: row.get("completion_tokens"),
+                duration_sum_ms: row.get("duration_sum_ms"),
+                duration_count: row.get("duration_count"),
+            })
+            .collect())
+    }
+}
+
+// 将查询结果行转换为翻译记录
+fn row_to_record(row: &SqliteRow) -> Result<TranslationRecord, sqlx::Error> {
+    let created_at = parse_time(row.get("created_at"))?;
+
+    Ok(TranslationRecord {
+        id: row.get("id"),
+        source_text: row.get("source_text"),
+        translated_text: row.get("translated_text"),
+        source_lang: row.get("source_lang"),
+        target_lang: row.get("target_lang"),
+        meta: TranslationMeta {
+            platform: row.get("platform"),
+            model_name: row.get("model_name"),
+            prompt_hash: row.get("prompt_hash"),
+            duration_ms: row.get("duration_ms"),
+            prompt_tokens: row.get("prompt_tokens"),
+            completion_tokens: row.get("completion_tokens"),
+        },
+        parent_id: row.get("parent_id"),
+        instruction: row.get("instruction"),
+        created_at,
+    })
+}
+
+fn parse_time(value: String) -> Result<DateTime<Utc>, sqlx::Error> {
+    Ok(DateTime::parse_from_rfc3339(&value)
+        .map_err(|e| sqlx::Error::Decode(Box::new(e)))?
+        .with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats;
+
+    async fn test_database() -> Database {
+        let dir = std::env::temp_dir().join(format!("tauri_yi_db_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db_path = dir.join("history.db");
+        std::fs::write(&db_path, b"").unwrap();
+        Database::new(db_path).await.unwrap()
+    }
+
+    // 按指定时间插入一条翻译记录，lang_pair 为（源语言，目标语言）
+    async fn insert_at(
+        database: &Database,
+        created_at: &str,
+        platform: Option<&str>,
+        model_name: Option<&str>,
+        lang_pair: (&str, &str),
+    ) {
+        sqlx::query(
+            r#"
+            INSERT INTO translation_history (
+                id, source_text, translated_text, source_lang, target_lang,
+                platform, model_name, created_at
+            )
+            VALUES (?, '你好', 'hello', ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(lang_pair.0)
+        .bind(lang_pair.1)
+        .bind(platform)
+        .bind(model_name)
+        .bind(created_at)
+        .execute(&*database.pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_recent_translations_skip_revisions() {
+        let database = test_database().await;
+        let meta = TranslationMeta::default();
+        let id = database
+            .save_translation("你好", "Hello", "zh", "en", &meta)
+            .await
+            .unwrap();
+        let original = database.get_translation(&id).await.unwrap().unwrap();
+        database
+            .save_revision(&original, "更正式", "Good day", &meta)
+            .await
+            .unwrap();
+        database
+            .save_translation("再见", "Bye", "zh", "en", &meta)
+            .await
+            .unwrap();
+
+        let since = Utc::now() - chrono::Duration::minutes(1);
+        let recent = database
+            .recent_translations(since, "zh", "en", 5)
+            .await
+            .unwrap();
+        let texts: Vec<_> = recent.iter().map(|r| r.translated_text.as_str()).collect();
+        assert_eq!(texts, ["Hello", "Bye"]);
+    }
+
+    #[tokio::test]
+    async fn test_usage_rows() {
+        let database = test_database().await;
+        let records = [
+            (
+                "2025-12-29T10:00:00+00:00",
+                Some("openai"),
+                Some("gpt-4o"),
+                ("zh", "en"),
+            ),
+            (
+                "2026-01-01T10:00:00+00:00",
+                Some("openai"),
+                Some("gpt-4o"),
+                ("zh", "en"),
+            ),
+            (
+                "2026-01-04T23:59:59.5+00:00",
+                Some("ollama"),
+                None,
+                ("en", "zh"),
+            ),
+            (
+                "2026-01-05T00:00:00+00:00",
+                Some("ollama"),
+                Some("qwen3"),
+                ("en", "zh"),
+            ),
+            ("2027-01-01T10:00:00+00:00", None, None, ("zh", "en")),
+        ];
+        for (created_at, platform, model_name, lang_pair) in records {
+            insert_at(&database, created_at, platform, model_name, lang_pair).await;
+        }
+
+        let cases = [
+            (
+                StatsGroupBy::Day,
+                vec![
+                    ("2025-12-29", 1),
+                    ("2026-01-01", 1),
+                    ("2026-01-04", 1),
+                    ("2026-01-05", 1),
+                    ("2027-01-01", 1),
+                ],
+            ),
+            // 2025-12-29 所在的一周属于 2026 年第 1 周，2027-01-01 属于 2026 年第 53 周
+            (
+                StatsGroupBy::Week,
+                vec![("2026-W01", 3), ("2026-W02", 1), ("2026-W53", 1)],
+            ),
+            (
+                StatsGroupBy::Month,
+                vec![("2025-12", 1), ("2026-01", 3), ("2027-01", 1)],
+            ),
+            (
+                StatsGroupBy::Platform,
+                vec![("ollama", 2), ("openai", 2), ("unknown", 1)],
+            ),
+            (
+                StatsGroupBy::Model,
+                vec![("gpt-4o", 2), ("ollama", 1), ("qwen3", 1), ("unknown", 1)],
+            ),
+            (StatsGroupBy::LangPair, vec![("en->zh", 2), ("zh->en", 3)]),
+            (StatsGroupBy::Total, vec![("total", 5)]),
+        ];
+        for (group_by, expected) in cases {
+            let rows = database.usage_rows(group_by, None, None).await.unwrap();
+            let stats: Vec<(String, i64)> = stats::aggregate(rows, &Default::default())
+                .into_iter()
+                .map(|stat| (stat.key, stat.translations))
+                .collect();
+            let expected: Vec<(String, i64)> = expected
+                .into_iter()
+                .map(|(key, count)| (key.to_string(), count))
+                .collect();
+            assert_eq!(stats, expected, "{:?}", group_by);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_usage_rows_skip_revisions() {
+        let database = test_database().await;
+        let meta = TranslationMeta {
+            prompt_tokens: Some(10),
+            ..Default::default()
+        };
+        let id = database
+            .save_translation("你好", "Hello", "zh", "en", &meta)
+            .await
+            .unwrap();
+        let original = database.get_translation(&id).await.unwrap().unwrap();
+        database
+            .save_revision(&original, "更正式", "Good day", &meta)
+            .await
+            .unwrap();
+
+        let rows = database
+            .usage_rows(StatsGroupBy::Total, None, None)
+            .await
+            .unwrap();
+        let total = stats::aggregate(rows, &Default::default()).pop().unwrap();
+        assert_eq!(total.translations, 1);
+        assert_eq!(total.characters, 2);
+        assert_eq!(total.prompt_tokens, 20);
+    }
+}
@@ -0,0 +1,267 @@
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::lang;
+
+mod stardict;
+
+/// 导入时的词条
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DictEntry {
+    pub word: String,
+    #[serde(default)]
+    pub phonetic: Option<String>,
+    pub definition: String,
+}
+
+/// 已导入的词典
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DictionaryInfo {
+    pub id: i64,
+    pub name: String,
+    /// 导入时的文件路径
+    pub source: String,
+    pub word_count: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 离线词典的查询结果
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DictionaryDefinition {
+    /// 词典名称
+    pub dictionary: String,
+    pub word: String,
+    pub phonetic: Option<String>,
+    pub definition: String,
+}
+
+/// 读取的词典文件
+#[derive(Debug)]
+pub struct DictionaryFile {
+    pub name: String,
+    pub entries: Vec<DictEntry>,
+}
+
+/// 是否查询离线词典：不含空白的单个单词，中日韩文字按字数判断
+pub fn is_single_word(text: &str) -> bool {
+    text.trim().chars().count() <= 64 && lang::is_word_or_phrase(text, 1)
+}
+
+/// 根据扩展名读取词典文件
+///
+/// 支持 StarDict（选择 `.ifo` 文件）、CSV（`单词,释义[,音标]`）和 JSON
+/// （`[{"word", "definition", "phonetic"}]` 数组或 `{"单词": "释义"}` 对象）。
+pub fn load(path: &Path) -> Result<DictionaryFile> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_lowercase)
+        .unwrap_or_default();
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("dictionary")
+        .to_string();
+
+    let file = match extension.as_str() {
+        "ifo" => stardict::load(path)?,
+        "csv" => DictionaryFile {
+            name: stem,
+            entries: parse_csv(&std::fs::read_to_string(path)?)?,
+        },
+        "json" => DictionaryFile {
+            name: stem,
+            entries: parse_json(&std::fs::read_to_string(path)?)?,
+        },
+        _ => bail!("不支持的词典格式: {}", path.display()),
+    };
+    if file.entries.is_empty() {
+        bail!("词典中没有词条: {}", path.display());
+    }
+    Ok(file)
+}
+
+fn parse_json(content: &str) -> Result<Vec<DictEntry>> {
+    let json: serde_json::Value = serde_json::from_str(content)?;
+    let entries = match json {
+        serde_json::Value::Array(_) => serde_json::from_value::<Vec<DictEntry>>(json)?,
+        serde_json::Value::Object(map) => map
+            .into_iter()
+            .filter_map(|(word, definition)| {
+                Some(DictEntry {
+                    word,
+                    phonetic: None,
+                    definition: definition.as_str()?.to_string(),
+                })
+            })
+            .collect(),
+        _ => bail!("JSON 词典必须是数组或对象"),
+    };
+    Ok(clean_entries(entries))
+}
+
+fn parse_csv(content: &str) -> Result<Vec<DictEntry>> {
+    let mut entries = Vec::new();
+    for (index, record) in csv_records(content)?.into_iter().enumerate() {
+        // 跳过表头
+        if index == 0
+            && record
+                .first()
+                .is_some_and(|f| f.eq_ignore_ascii_case("word"))
+        {
+            continue;
+        }
+        let mut fields = record.into_iter();
+        let (Some(word), Some(definition)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        entries.push(DictEntry {
+            word,
+            definition,
+            phonetic: fields.next().filter(|p| !p.trim().is_empty()),
+        });
+    }
+    Ok(clean_entries(entries))
+}
+
+// 按 RFC 4180 拆分 CSV，支持引号中的逗号、换行和 "" 转义
+fn csv_records(content: &str) -> Result<Vec<Vec<String>>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if field.is_empty() => in_quotes = true,
+            (',', false) => record.push(std::mem::take(&mut field)),
+            ('\r', false) => {}
+            ('\n', false) => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        bail!("CSV 中的引号没有闭合");
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+// 去除首尾空白，丢弃单词或释义为空的词条
+fn clean_entries(entries: Vec<DictEntry>) -> Vec<DictEntry> {
+    entries
+        .into_iter()
+        .filter_map(|entry| {
+            let word = entry.word.trim().to_string();
+            let definition = entry.definition.trim().to_string();
+            (!word.is_empty() && !definition.is_empty()).then(|| DictEntry {
+                word,
+                definition,
+                phonetic: entry.phonetic.map(|p| p.trim().to_string()),
+            })
+        })
+        .collect()
+}
+
+/// 从文件导入词典到数据库
+pub async fn import(database: &crate::database::Database, path: &Path) -> Result<DictionaryInfo> {
+    let owned = path.to_path_buf();
+    let file = tokio::task::spawn_blocking(move || load(&owned))
+        .await
+        .map_err(|e| anyhow!("读取词典失败: {}", e))??;
+    let info = database
+        .import_dictionary(&file.name, &path.display().to_string(), &file.entries)
+        .await?;
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_csv() {
+        let content = "word,definition,phonetic\r\nrun,\"v. 跑, 运行\",/rʌn/\n\"say \"\"hi\"\"\",\"打招呼\n问好\"\n,空单词\n";
+        let entries = parse_csv(content).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].definition, "v. 跑, 运行");
+        assert_eq!(entries[0].phonetic.as_deref(), Some("/rʌn/"));
+        assert_eq!(entries[1].word, "say \"hi\"");
+        assert_eq!(entries[1].definition, "打招呼\n问好");
+        assert!(csv_records("\"unterminated").is_err());
+    }
+
+    #[test]
+    fn test_parse_json() {
+        let array = r#"[{"word": "run", "definition": "跑", "phonetic": "/rʌn/"}]"#;
+        assert_eq!(
+            parse_json(array).unwrap()[0].phonetic.as_deref(),
+            Some("/rʌn/")
+        );
+        let object = r#"{"run": "跑", "set": "设置", "bad": 1}"#;
+        assert_eq!(parse_json(object).unwrap().len(), 2);
+        assert!(parse_json("\"run\"").is_err());
+    }
+
+    #[test]
+    fn test_is_single_word() {
+        assert!(is_single_word(" Run "));
+        assert!(is_single_word("天气"));
+        assert!(!is_single_word("take off"));
+        // 不含空格的中日文句子不查询离线词典
+        assert!(!is_single_word("这个功能非常好用"));
+        assert!(!is_single_word("ありがとうございます"));
+    }
+
+    #[tokio::test]
+    async fn test_import_and_lookup() {
+        let dir = std::env::temp_dir().join(format!("tauri_yi_dict_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db_path = dir.join("history.db");
+        std::fs::write(&db_path, b"").unwrap();
+        let csv_path = dir.join("basic.csv");
+        std::fs::write(&csv_path, "Run,跑\nset,设置\n").unwrap();
+
+        let database = crate::database::Database::new(db_path).await.unwrap();
+        let info = import(&database, &csv_path).await.unwrap();
+        assert_eq!(info.name, "basic");
+        assert_eq!(info.word_count, 2);
+
+        let results = database.lookup_word("run").await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].dictionary, "basic");
+        assert_eq!(results[0].definition, "跑");
+
+        // 重新导入同名词典会替换原有词条
+        std::fs::write(&csv_path, "run,运行\n").unwrap();
+        import(&database, &csv_path).await.unwrap();
+        let dictionaries = database.list_dictionaries().await.unwrap();
+        assert_eq!(dictionaries.len(), 1);
+        assert_eq!(
+            database.lookup_word("RUN").await.unwrap()[0].definition,
+            "运行"
+        );
+        assert!(database.lookup_word("set").await.unwrap().is_empty());
+
+        assert!(database
+            .remove_dictionary(dictionaries[0].id)
+            .await
+            .unwrap());
+        assert!(database.lookup_word("run").await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,284 @@
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Result};
+use flate2::read::GzDecoder;
+
+use super::{clean_entries, DictEntry, DictionaryFile};
+
+/// 读取 StarDict 词典，`ifo_path` 为 `.ifo` 文件，同目录下需要有同名的 `.idx` 和 `.dict` 文件
+///
+/// `.idx` 和 `.dict` 也可以是 gzip 压缩的 `.idx.gz` 和 `.dict.dz`。
+pub fn load(ifo_path: &Path) -> Result<DictionaryFile> {
+    let ifo = parse_ifo(&std::fs::read_to_string(ifo_path)?)?;
+    let idx = read_companion(ifo_path, &["idx", "idx.gz"])?;
+    let dict = read_companion(ifo_path, &["dict", "dict.dz"])?;
+
+    let offset_bits = match ifo.get("idxoffsetbits").map(String::as_str) {
+        None | Some("32") => 32,
+        Some("64") => 64,
+        Some(bits) => bail!("不支持的 idxoffsetbits: {}", bits),
+    };
+    let same_type_sequence = ifo.get("sametypesequence").map(String::as_str);
+
+    let mut entries = Vec::new();
+    for (word, offset, size) in parse_idx(&idx, offset_bits)? {
+        // 偏移量来自文件，格式错误时相加可能溢出
+        let data = offset
+            .checked_add(size)
+            .and_then(|end| dict.get(offset..end))
+            .ok_or_else(|| anyhow!("词条 {} 的数据超出 .dict 文件范围", word))?;
+        let (phonetic, definition) = parse_data(data, same_type_sequence)?;
+        entries.push(DictEntry {
+            word,
+            phonetic,
+            definition,
+        });
+    }
+
+    let name = ifo
+        .get("bookname")
+        .cloned()
+        .or_else(|| {
+            ifo_path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| "StarDict".to_string());
+    Ok(DictionaryFile {
+        name,
+        entries: clean_entries(entries),
+    })
+}
+
+fn parse_ifo(content: &str) -> Result<HashMap<String, String>> {
+    let mut lines = content.lines();
+    if lines.next().map(str::trim) != Some("StarDict's dict ifo file") {
+        bail!("不是有效的 StarDict .ifo 文件");
+    }
+    Ok(lines
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect())
+}
+
+// 读取与 .ifo 同名的文件，.gz 和 .dz 需要解压
+fn read_companion(ifo_path: &Path, extensions: &[&str]) -> Result<Vec<u8>> {
+    for extension in extensions {
+        let path: PathBuf = ifo_path.with_extension(extension);
+        if !path.exists() {
+            continue;
+        }
+        let bytes = std::fs::read(&path)?;
+        if extension.ends_with("gz") || extension.ends_with("dz") {
+            let mut decoded = Vec::new();
+            GzDecoder::new(bytes.as_slice()).read_to_end(&mut decoded)?;
+            return Ok(decoded);
+        }
+        return Ok(bytes);
+    }
+    bail!(
+        "未找到 StarDict 文件: {}",
+        ifo_path.with_extension(extensions[0]).display()
+    )
+}
+
+// 每个索引项为：以 \0 结尾的单词、数据偏移量（32 或 64 位）、数据长度（32 位），均为大端序
+fn parse_idx(idx: &[u8], offset_bits: usize) -> Result<Vec<(String, usize, usize)>> {
+    let offset_len = offset_bits / 8;
+    let mut items = Vec::new();
+    let mut rest = idx;
+    while !rest.is_empty() {
+        let end = rest
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or_else(|| anyhow!(".idx 文件格式错误"))?;
+        let word = String::from_utf8_lossy(&rest[..end]).into_owned();
+        rest = &rest[end + 1..];
+        if rest.len() < offset_len + 4 {
+            bail!(".idx 文件格式错误");
+        }
+        let offset = read_be(&rest[..offset_len]);
+        let size = read_be(&rest[offset_len..offset_len + 4]);
+        rest = &rest[offset_len + 4..];
+        items.push((word, offset, size));
+    }
+    Ok(items)
+}
+
+fn read_be(bytes: &[u8]) -> usize {
+    bytes
+        .iter()
+        .fold(0usize, |value, &byte| (value << 8) | usize::from(byte))
+}
+
+/// 解析词条数据，返回（音标，释义）
+///
+/// 小写类型的字段以 \0 结尾，大写类型的字段以 32 位长度开头；
+/// 使用 sametypesequence 时字段不带类型标记，且最后一个字段没有结尾标记和长度。
+fn parse_data(data: &[u8], same_type_sequence: Option<&str>) -> Result<(Option<String>, String)> {
+    let mut fields: Vec<(u8, &[u8])> = Vec::new();
+    let mut rest = data;
+    match same_type_sequence {
+        Some(sequence) if !sequence.is_empty() => {
+            let types = sequence.as_bytes();
+            for (index, &field_type) in types.iter().enumerate() {
+                let last = index == types.len() - 1;
+                let (field, remaining) = split_field(rest, field_type, last)?;
+                fields.push((field_type, field));
+                rest = remaining;
+            }
+        }
+        _ => {
+            while let Some((&field_type, remaining)) = rest.split_first() {
+                let (field, remaining) = split_field(remaining, field_type, false)?;
+                fields.push((field_type, field));
+                rest = remaining;
+            }
+        }
+    }
+
+    let mut phonetic = None;
+    let mut definitions = Vec::new();
+    for (field_type, field) in fields {
+        let text = String::from_utf8_lossy(field);
+        match field_type {
+            b't' => phonetic = Some(text.trim().to_string()),
+            b'm' | b'l' | b'y' | b'k' | b'w' => definitions.push(text.trim().to_string()),
+            b'h' | b'g' | b'x' => definitions.push(strip_markup(&text)),
+            // 图片、音频等二进制数据
+            _ => {}
+        }
+    }
+    Ok((phonetic, definitions.join("\n")))
+}
+
+fn split_field(data: &[u8], field_type: u8, last: bool) -> Result<(&[u8], &[u8])> {
+    if last {
+        return Ok((data, &[]));
+    }
+    if field_type.is_ascii_lowercase() {
+        let end = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+        Ok((&data[..end], data.get(end + 1..).unwrap_or_default()))
+    } else {
+        if data.len() < 4 {
+            bail!(".dict 文件格式错误");
+        }
+        let size = read_be(&data[..4]);
+        let field = data
+            .get(4..4 + size)
+            .ok_or_else(|| anyhow!(".dict 文件格式错误"))?;
+        Ok((field, &data[4 + size..]))
+    }
+}
+
+// 去除 HTML/XML 标签，<br> 转为换行
+fn strip_markup(text: &str) -> String {
+    let text = text
+        .replace("<br>", "\n")
+        .replace("<br/>", "\n")
+        .replace("<br />", "\n");
+    let mut output = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => output.push(c),
+            _ => {}
+        }
+    }
+    output
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&amp;", "&")
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::write::GzEncoder;
+
+    use super::*;
+
+    // 生成 StarDict 文件，返回 .ifo 路径
+    fn write_stardict(words: &[(&str, &[u8])], same_type_sequence: Option<&str>) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tauri_yi_stardict_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut idx = Vec::new();
+        let mut dict = Vec::new();
+        for (word, data) in words {
+            idx.extend_from_slice(word.as_bytes());
+            idx.push(0);
+            idx.extend_from_slice(&(dict.len() as u32).to_be_bytes());
+            idx.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            dict.extend_from_slice(data);
+        }
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&dict).unwrap();
+
+        let mut ifo = format!(
+            "StarDict's dict ifo file\nversion=2.4.2\nbookname=Test Dict\nwordcount={}\nidxfilesize={}\n",
+            words.len(),
+            idx.len()
+        );
+        if let Some(sequence) = same_type_sequence {
+            ifo.push_str(&format!("sametypesequence={}\n", sequence));
+        }
+        let ifo_path = dir.join("test.ifo");
+        std::fs::write(&ifo_path, ifo).unwrap();
+        std::fs::write(dir.join("test.idx"), idx).unwrap();
+        std::fs::write(dir.join("test.dict.dz"), encoder.finish().unwrap()).unwrap();
+        ifo_path
+    }
+
+    #[test]
+    fn test_load_same_type_sequence() {
+        let ifo_path = write_stardict(
+            &[
+                ("run", b"/r\xca\x8cn/\0v. \xe8\xb7\x91"),
+                ("set", b"/set/\0v. \xe8\xae\xbe\xe7\xbd\xae"),
+            ],
+            Some("tm"),
+        );
+        let file = load(&ifo_path).unwrap();
+        assert_eq!(file.name, "Test Dict");
+        assert_eq!(file.entries.len(), 2);
+        assert_eq!(file.entries[0].phonetic.as_deref(), Some("/rʌn/"));
+        assert_eq!(file.entries[0].definition, "v. 跑");
+    }
+
+    #[test]
+    fn test_load_typed_fields() {
+        let ifo_path = write_stardict(&[("run", b"h<b>v.</b> run<br>fast\0m\xe8\xb7\x91\0")], None);
+        let file = load(&ifo_path).unwrap();
+        assert_eq!(file.entries[0].definition, "v. run\nfast\n跑");
+        assert!(file.entries[0].phonetic.is_none());
+    }
+
+    #[test]
+    fn test_load_offset_overflow() {
+        let ifo_path = write_stardict(&[("run", b"m\xe8\xb7\x91\0")], None);
+        let dir = ifo_path.parent().unwrap();
+        let ifo = std::fs::read_to_string(&ifo_path).unwrap();
+        std::fs::write(&ifo_path, format!("{}idxoffsetbits=64\n", ifo)).unwrap();
+        let mut idx = b"run\0".to_vec();
+        idx.extend_from_slice(&u64::MAX.to_be_bytes());
+        idx.extend_from_slice(&16u32.to_be_bytes());
+        std::fs::write(dir.join("test.idx"), idx).unwrap();
+
+        assert!(load(&ifo_path).is_err());
+    }
+
+    #[test]
+    fn test_invalid_ifo() {
+        assert!(parse_ifo("not a dictionary").is_err());
+    }
+}
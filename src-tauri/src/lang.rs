@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum Lang {
     Zh,
@@ -11,6 +11,19 @@ pub enum Lang {
 }
 
 impl Lang {
+    pub const ALL: [Lang; 5] = [Lang::Auto, Lang::Zh, Lang::En, Lang::Ja, Lang::Ko];
+
+    /// 界面上显示的语言名称
+    pub fn label(self) -> &'static str {
+        match self {
+            Lang::Zh => "中文",
+            Lang::En => "英语",
+            Lang::Ja => "日语",
+            Lang::Ko => "韩语",
+            Lang::Auto => "自动检测",
+        }
+    }
+
     pub fn to_full_name(self) -> &'static str {
         match self {
             Lang::Zh => "chinese",
@@ -21,6 +34,17 @@ impl Lang {
         }
     }
 
+    // 从数据库中保存的语言代码还原
+    pub fn from_code(code: &str) -> Option<Lang> {
+        match code {
+            "zh" => Some(Lang::Zh),
+            "en" => Some(Lang::En),
+            "ja" => Some(Lang::Ja),
+            "ko" => Some(Lang::Ko),
+            "auto" => Some(Lang::Auto),
+            _ => None,
+        }
+    }
 }
 
 impl From<Lang> for String {
@@ -47,6 +71,33 @@ impl From<Lang> for &str {
     }
 }
 
+// 中日韩文字不以空格分词，单词或短语最多包含的字数
+const MAX_CJK_CHARS: usize = 4;
+
+/// 是否为中日韩文字（汉字、假名、谚文）
+pub fn is_cjk(c: char) -> bool {
+    matches!(
+        c,
+        '\u{1100}'..='\u{11FF}'
+            | '\u{3040}'..='\u{30FF}'
+            | '\u{3130}'..='\u{318F}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{AC00}'..='\u{D7AF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{20000}'..='\u{2FA1F}'
+    )
+}
+
+/// 是否为单词或短语：单行，最多 `max_words` 个以空白分隔的部分，且中日韩文字不超过 4 个字
+pub fn is_word_or_phrase(text: &str, max_words: usize) -> bool {
+    let text = text.trim();
+    !text.is_empty()
+        && !text.contains('\n')
+        && text.split_whitespace().count() <= max_words
+        && text.chars().filter(|c| is_cjk(*c)).count() <= MAX_CJK_CHARS
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -56,5 +107,20 @@ mod tests {
         assert_eq!(Lang::En.to_full_name(), "english");
         assert_eq!(Lang::Ja.to_full_name(), "japanese");
         assert_eq!(Lang::Ko.to_full_name(), "korean");
+        assert!(matches!(Lang::from_code("ja"), Some(Lang::Ja)));
+        assert!(Lang::from_code("fr").is_none());
+    }
+
+    #[test]
+    fn test_is_word_or_phrase() {
+        assert!(is_word_or_phrase("take off", 3));
+        assert!(!is_word_or_phrase("take off", 1));
+        assert!(is_word_or_phrase("一石二鸟", 1));
+        assert!(is_word_or_phrase("T恤", 1));
+        // 中日韩文字的句子不含空格，按字数判断
+        assert!(!is_word_or_phrase("今天天气很好", 3));
+        assert!(!is_word_or_phrase("この機能はとても便利です", 3));
+        assert!(!is_word_or_phrase("오늘 날씨가 좋네요", 3));
+        assert!(!is_word_or_phrase("Run\nSet", 3));
     }
 }
@@ -1,16 +1,35 @@
+#[cfg(desktop)]
+mod actions;
 mod ai;
 mod app_setup;
+#[cfg(desktop)]
+mod app_window;
+#[cfg(desktop)]
+mod clipboard_watch;
 mod commands;
 mod config;
 mod database;
+mod dictionary;
 mod lang;
+#[cfg(desktop)]
+mod notification;
+mod position;
 mod resp;
+#[cfg(desktop)]
+mod selection;
+#[cfg(desktop)]
+mod shortcut;
+mod state;
+mod stats;
 mod tray_menu;
 mod utils;
 
+use tauri::{path::BaseDirectory, Manager};
 #[cfg(target_os = "macos")]
 use tauri::ActivationPolicy;
 
+use crate::state::AppState;
+
 #[cfg_attr(
     mobile,
     tauri::mobile_entry_point,
@@ -21,27 +40,44 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_notification::init())
         .setup(|app| {
             // 初始化配置文件
-            config::init_config(app);
+            let config_path = app
+                .path()
+                .resolve(config::CONFIG_PATH, BaseDirectory::Resource)?;
+            let db_path = app
+                .path()
+                .resolve(config::DB_FILE_PATH, BaseDirectory::Resource)?;
+            let app_config = config::init_config(&config_path);
+            app.manage(AppState::new(app_config, config_path, db_path));
 
-            // 初始化数据库
+            // 提前初始化数据库，命令在初始化完成前会等待而不是失败
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
-                if let Err(e) = config::init_database(&app_handle).await {
+                if let Err(e) = app_handle.state::<AppState>().database().await {
                     eprintln!("数据库初始化失败: {}", e);
                 }
             });
 
+            // 预加载 Ollama 模型，避免第一次翻译等待模型加载
+            let provider = app.state::<AppState>().config().provider;
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = ai::warm_up(&provider).await {
+                    eprintln!("{}", e);
+                }
+            });
+
             // 应用启动后的设置，可以在这里注册快捷键
             #[cfg(desktop)]
             app_setup::setup_desktop(app.handle())?;
+            // 剪贴板监听，未开启时只检查配置
+            #[cfg(desktop)]
+            clipboard_watch::start(app.handle().clone());
             // 构建主窗口
             app_setup::build_main_window(app.handle())?;
             // 构建托盘菜单
             tray_menu::setup_tray_icon(app.handle())?;
-            // 监控main窗口状态
-            app_setup::monitor_main_window_state(app.handle().clone());
 
             #[cfg(target_os = "macos")]
             app.set_activation_policy(ActivationPolicy::Accessory);
@@ -49,14 +85,30 @@ pub fn run() {
         })
         .invoke_handler(tauri::generate_handler![
             commands::translate,
+            commands::refine_translation,
+            commands::translate_dictionary,
+            commands::translate_compare,
+            commands::save_compare_result,
             commands::close_window,
+            commands::open_window,
+            commands::set_pinned,
             commands::load_config,
             commands::update_config,
             commands::reset_config,
+            commands::set_lang_pair,
             commands::get_translation_history,
             commands::search_translations,
             commands::delete_translation,
             commands::clear_translation_history,
+            commands::import_dictionary,
+            commands::list_dictionaries,
+            commands::remove_dictionary,
+            commands::lookup_dictionary,
+            commands::get_usage_stats,
+            commands::get_usage_summary,
+            commands::list_models,
+            commands::show_model,
+            commands::pull_model,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
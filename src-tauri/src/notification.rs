@@ -0,0 +1,89 @@
+use anyhow::Result;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::state::AppState;
+use crate::utils;
+
+/// 通知中显示的翻译结果，点击通知时在主窗口中显示完整的原文和译文
+#[derive(Debug, Clone, Serialize)]
+pub struct TranslationResult {
+    /// 翻译记录 ID，在主窗口中调整译文时使用
+    pub id: String,
+    pub source_text: String,
+    pub translated_text: String,
+}
+
+/// 显示系统通知
+pub fn show(app_handle: &AppHandle, title: &str, body: &str) -> Result<()> {
+    app_handle
+        .notification()
+        .builder()
+        .title(title)
+        .body(body)
+        .show()?;
+    Ok(())
+}
+
+/// 显示翻译结果通知，只显示译文的第一行，过长时截断
+///
+/// Linux 上点击通知会在主窗口中显示完整的原文和译文；其他系统的通知不支持点击操作，
+/// 可以从托盘菜单的最近翻译中复制完整译文。
+pub fn show_result(app_handle: &AppHandle, title: &str, result: TranslationResult) -> Result<()> {
+    let body = truncate(app_handle, &result.translated_text);
+    show_clickable(app_handle, title, &body, result)
+}
+
+/// 显示错误通知
+pub fn show_error(app_handle: &AppHandle, title: &str, error: &anyhow::Error) {
+    let body = truncate(app_handle, &error.to_string());
+    if let Err(e) = show(app_handle, title, &body) {
+        eprintln!("显示通知失败: {}", e);
+    }
+}
+
+fn truncate(app_handle: &AppHandle, text: &str) -> String {
+    let max_length = app_handle
+        .state::<AppState>()
+        .config()
+        .notification
+        .max_length;
+    utils::truncate(text, max_length)
+}
+
+// 通知插件在桌面端不支持点击回调，Linux 上直接通过 D-Bus 发送通知并等待点击
+#[cfg(target_os = "linux")]
+fn show_clickable(
+    app_handle: &AppHandle,
+    title: &str,
+    body: &str,
+    result: TranslationResult,
+) -> Result<()> {
+    let handle = notify_rust::Notification::new()
+        .appname(&app_handle.package_info().name)
+        .summary(title)
+        .body(body)
+        .action("default", "查看完整译文")
+        .show()?;
+    let app_handle = app_handle.clone();
+    // 等待点击会阻塞到通知关闭，在单独的线程中等待
+    std::thread::spawn(move || {
+        handle.wait_for_action(|action| {
+            if action == "default" {
+                crate::actions::show_result(&app_handle, result);
+            }
+        });
+    });
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn show_clickable(
+    app_handle: &AppHandle,
+    title: &str,
+    body: &str,
+    _result: TranslationResult,
+) -> Result<()> {
+    show(app_handle, title, body)
+}
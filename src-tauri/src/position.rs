@@ -0,0 +1,252 @@
+use crate::config::{PopupPlacement, ScreenPosition};
+
+// 窗口与鼠标的距离，单位为逻辑像素
+const CURSOR_OFFSET: f64 = 10.0;
+// 窗口与屏幕边缘的距离，单位为逻辑像素
+const EDGE_MARGIN: f64 = 20.0;
+
+/// 显示器区域，位置和大小为物理像素
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MonitorArea {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+}
+
+impl MonitorArea {
+    fn left(&self) -> f64 {
+        f64::from(self.x)
+    }
+
+    fn top(&self) -> f64 {
+        f64::from(self.y)
+    }
+
+    fn right(&self) -> f64 {
+        self.left() + f64::from(self.width)
+    }
+
+    fn bottom(&self) -> f64 {
+        self.top() + f64::from(self.height)
+    }
+
+    fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.left() && x < self.right() && y >= self.top() && y < self.bottom()
+    }
+
+    // 点到显示器区域的距离的平方，点在区域内时为 0
+    fn distance_squared(&self, x: f64, y: f64) -> f64 {
+        let dx = (self.left() - x).max(x - self.right()).max(0.0);
+        let dy = (self.top() - y).max(y - self.bottom()).max(0.0);
+        dx * dx + dy * dy
+    }
+
+    // 把窗口限制在显示器内，窗口比显示器大时与左上角对齐
+    fn clamp(&self, x: f64, y: f64, width: f64, height: f64) -> ScreenPosition {
+        let x = x.min(self.right() - width).max(self.left());
+        let y = y.min(self.bottom() - height).max(self.top());
+        ScreenPosition {
+            x: x.round() as i32,
+            y: y.round() as i32,
+        }
+    }
+}
+
+/// 查找包含该点的显示器，点不在任何显示器内时返回距离最近的显示器
+pub fn monitor_at(monitors: &[MonitorArea], x: f64, y: f64) -> Option<&MonitorArea> {
+    monitors
+        .iter()
+        .find(|monitor| monitor.contains(x, y))
+        .or_else(|| {
+            monitors.iter().min_by(|a, b| {
+                a.distance_squared(x, y)
+                    .total_cmp(&b.distance_squared(x, y))
+            })
+        })
+}
+
+/// 计算翻译窗口左上角的位置，没有显示器时返回 None
+///
+/// `cursor` 为鼠标的物理坐标；`size` 为窗口的逻辑大小，按目标显示器的缩放比例换算为物理像素。
+pub fn popup_position(
+    placement: PopupPlacement,
+    monitors: &[MonitorArea],
+    cursor: (f64, f64),
+    size: (f64, f64),
+    last_position: Option<ScreenPosition>,
+) -> Option<ScreenPosition> {
+    if placement == PopupPlacement::LastPosition {
+        if let Some(last) = last_position {
+            let (x, y) = (f64::from(last.x), f64::from(last.y));
+            if let Some(monitor) = monitors.iter().find(|monitor| monitor.contains(x, y)) {
+                let scale = monitor.scale_factor;
+                return Some(monitor.clamp(x, y, size.0 * scale, size.1 * scale));
+            }
+        }
+    }
+
+    let (cursor_x, cursor_y) = cursor;
+    let monitor = monitor_at(monitors, cursor_x, cursor_y)?;
+    let scale = monitor.scale_factor;
+    let (width, height) = (size.0 * scale, size.1 * scale);
+    let offset = CURSOR_OFFSET * scale;
+    let margin = EDGE_MARGIN * scale;
+
+    let (x, y) = match placement {
+        PopupPlacement::NearCursor | PopupPlacement::LastPosition => {
+            // 默认显示在鼠标右下方，右侧或下方空间不足时显示在左侧或上方
+            let mut x = cursor_x + offset;
+            if x + width > monitor.right() {
+                x = cursor_x - offset - width;
+            }
+            let mut y = cursor_y + offset;
+            if y + height > monitor.bottom() {
+                y = cursor_y - offset - height;
+            }
+            (x, y)
+        }
+        PopupPlacement::Center => (
+            monitor.left() + (f64::from(monitor.width) - width) / 2.0,
+            monitor.top() + (f64::from(monitor.height) - height) / 2.0,
+        ),
+        PopupPlacement::TopLeft => (monitor.left() + margin, monitor.top() + margin),
+        PopupPlacement::TopRight => (monitor.right() - margin - width, monitor.top() + margin),
+        PopupPlacement::BottomLeft => (monitor.left() + margin, monitor.bottom() - margin - height),
+        PopupPlacement::BottomRight => (
+            monitor.right() - margin - width,
+            monitor.bottom() - margin - height,
+        ),
+    };
+    Some(monitor.clamp(x, y, width, height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: (f64, f64) = (300.0, 350.0);
+
+    fn monitor(x: i32, y: i32, width: u32, height: u32, scale_factor: f64) -> MonitorArea {
+        MonitorArea {
+            x,
+            y,
+            width,
+            height,
+            scale_factor,
+        }
+    }
+
+    // 主显示器 1920x1080，左侧 2560x1440 的显示器，右侧缩放比例为 2 的 4K 显示器
+    fn monitors() -> Vec<MonitorArea> {
+        vec![
+            monitor(0, 0, 1920, 1080, 1.0),
+            monitor(-2560, 0, 2560, 1440, 1.0),
+            monitor(1920, 0, 3840, 2160, 2.0),
+        ]
+    }
+
+    fn position(x: i32, y: i32) -> Option<ScreenPosition> {
+        Some(ScreenPosition { x, y })
+    }
+
+    fn place(placement: PopupPlacement, cursor: (f64, f64)) -> Option<ScreenPosition> {
+        popup_position(placement, &monitors(), cursor, SIZE, None)
+    }
+
+    #[test]
+    fn test_monitor_at() {
+        let monitors = monitors();
+        assert_eq!(monitor_at(&monitors, -1.0, 10.0), Some(&monitors[1]));
+        assert_eq!(monitor_at(&monitors, 1920.0, 10.0), Some(&monitors[2]));
+        // 不在任何显示器内时使用最近的显示器
+        assert_eq!(monitor_at(&monitors, 100.0, 1100.0), Some(&monitors[0]));
+        assert_eq!(monitor_at(&[], 0.0, 0.0), None);
+    }
+
+    #[test]
+    fn test_near_cursor() {
+        let near = PopupPlacement::NearCursor;
+        assert_eq!(place(near, (100.0, 100.0)), position(110, 110));
+        // 靠近右下角时显示在鼠标左上方
+        assert_eq!(place(near, (1900.0, 1000.0)), position(1590, 640));
+        // 左侧显示器的坐标为负数，右侧空间不足时不会跨到主显示器
+        assert_eq!(place(near, (-100.0, 50.0)), position(-410, 60));
+        assert_eq!(place(near, (-2550.0, 50.0)), position(-2540, 60));
+        // 缩放比例为 2 的显示器上窗口和偏移量都按物理像素加倍
+        assert_eq!(place(near, (2000.0, 100.0)), position(2020, 120));
+    }
+
+    #[test]
+    fn test_fixed_placement() {
+        assert_eq!(
+            place(PopupPlacement::Center, (100.0, 100.0)),
+            position(810, 365)
+        );
+        assert_eq!(
+            place(PopupPlacement::Center, (3000.0, 100.0)),
+            position(3540, 730)
+        );
+        assert_eq!(
+            place(PopupPlacement::TopLeft, (-100.0, 100.0)),
+            position(-2540, 20)
+        );
+        assert_eq!(
+            place(PopupPlacement::TopRight, (3000.0, 100.0)),
+            position(5120, 40)
+        );
+        assert_eq!(
+            place(PopupPlacement::BottomLeft, (3000.0, 100.0)),
+            position(1960, 1420)
+        );
+        assert_eq!(
+            place(PopupPlacement::BottomRight, (100.0, 100.0)),
+            position(1600, 710)
+        );
+    }
+
+    #[test]
+    fn test_last_position() {
+        let last = |x, y| {
+            popup_position(
+                PopupPlacement::LastPosition,
+                &monitors(),
+                (100.0, 100.0),
+                SIZE,
+                position(x, y),
+            )
+        };
+        assert_eq!(last(-1000, 200), position(-1000, 200));
+        // 超出显示器的部分移回显示器内
+        assert_eq!(last(1800, 900), position(1620, 730));
+        // 上次的位置已不在任何显示器内时显示在鼠标附近
+        assert_eq!(last(9000, 200), position(110, 110));
+        assert_eq!(
+            popup_position(
+                PopupPlacement::LastPosition,
+                &monitors(),
+                (100.0, 100.0),
+                SIZE,
+                None
+            ),
+            position(110, 110)
+        );
+    }
+
+    #[test]
+    fn test_clamp() {
+        // 鼠标上下方的空间都不足时限制在显示器内
+        let low = [monitor(0, 0, 1280, 600, 1.0)];
+        assert_eq!(
+            popup_position(PopupPlacement::NearCursor, &low, (100.0, 300.0), SIZE, None),
+            position(110, 0)
+        );
+        // 窗口比显示器大时与显示器左上角对齐
+        let small = [monitor(0, 0, 200, 200, 1.0)];
+        assert_eq!(
+            popup_position(PopupPlacement::Center, &small, (50.0, 50.0), SIZE, None),
+            position(0, 0)
+        );
+    }
+}
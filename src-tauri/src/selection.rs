@@ -0,0 +1,107 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::config::SelectionConfig;
+use crate::{clipboard_watch, utils};
+
+/// 读取当前选中的文本，读取不到时返回剪贴板内容
+///
+/// Linux 上优先读取 X11 PRIMARY 选区；开启 `simulateCopy` 时模拟复制快捷键读取选中的文本，
+/// 读取后恢复原有的剪贴板内容。
+pub async fn read(app_handle: &AppHandle, config: &SelectionConfig) -> Result<String> {
+    match read_selected(app_handle, config, config.simulate_copy).await {
+        Some(text) => Ok(text),
+        None => Ok(app_handle.clipboard().read_text()?),
+    }
+}
+
+/// 只读取选中的文本，不使用剪贴板内容，`simulate_copy` 为 true 时允许模拟复制
+pub async fn read_selected(
+    app_handle: &AppHandle,
+    config: &SelectionConfig,
+    simulate_copy: bool,
+) -> Option<String> {
+    #[cfg(target_os = "linux")]
+    if config.use_primary {
+        if let Some(text) = read_primary() {
+            return Some(text);
+        }
+    }
+    if !simulate_copy {
+        return None;
+    }
+    copy_selection(app_handle, config)
+        .await
+        .unwrap_or_else(|e| {
+            eprintln!("模拟复制失败: {}", e);
+            None
+        })
+}
+
+/// 把文本写入剪贴板并模拟粘贴，粘贴完成后恢复原有的剪贴板内容
+pub async fn paste(app_handle: &AppHandle, config: &SelectionConfig, text: String) -> Result<()> {
+    let clipboard = app_handle.clipboard();
+    let previous = clipboard.read_text().ok();
+    // 写入的译文不是用户复制的，剪贴板监听不翻译
+    clipboard_watch::ignore(app_handle, &text);
+    clipboard.write_text(text)?;
+
+    let pasted = utils::simulate_paste();
+    // 等待目标程序读取剪贴板后再恢复
+    tokio::time::sleep(Duration::from_millis(config.paste_delay_ms)).await;
+    if let Some(previous) = previous {
+        clipboard.write_text(previous)?;
+    }
+    pasted
+}
+
+#[cfg(target_os = "linux")]
+fn read_primary() -> Option<String> {
+    use arboard::{Clipboard, GetExtLinux, LinuxClipboardKind};
+
+    let mut clipboard = Clipboard::new().ok()?;
+    clipboard
+        .get()
+        .clipboard(LinuxClipboardKind::Primary)
+        .text()
+        .ok()
+        .filter(|text| !text.trim().is_empty())
+}
+
+// 模拟复制并读取剪贴板，没有复制到文本时返回 None
+//
+// 原有内容不是文本（如图片、文件）时无法恢复，不清空剪贴板，以免没有选中文本时丢失原有内容；
+// 复制到文本时与手动复制一样，复制的文本会保留在剪贴板中。
+async fn copy_selection(
+    app_handle: &AppHandle,
+    config: &SelectionConfig,
+) -> Result<Option<String>> {
+    let clipboard = app_handle.clipboard();
+    let previous = clipboard.read_text().ok();
+    if previous.is_some() {
+        // 先清空剪贴板，复制后仍为空说明没有选中文本
+        clipboard.clear()?;
+    }
+
+    let copied = match utils::simulate_copy() {
+        Ok(()) => {
+            tokio::time::sleep(Duration::from_millis(config.copy_delay_ms)).await;
+            let copied = clipboard.read_text().ok();
+            // 模拟复制的内容不是用户复制的，剪贴板监听不翻译
+            if let Some(copied) = &copied {
+                clipboard_watch::ignore(app_handle, copied);
+            }
+            Ok(copied.filter(|text| !text.trim().is_empty()))
+        }
+        Err(e) => Err(e),
+    };
+
+    // 恢复原有的剪贴板内容
+    if let Some(previous) = previous {
+        clipboard.write_text(previous)?;
+    }
+    copied
+}
@@ -0,0 +1,203 @@
+use anyhow::{anyhow, bail, Result};
+use tauri_plugin_global_shortcut::{Code, Shortcut};
+
+use crate::config::ShortcutConfig;
+
+/// 解析快捷键字符串，除 F1~F24 外必须包含修饰键
+pub fn parse(accelerator: &str) -> Result<Shortcut> {
+    let shortcut: Shortcut = accelerator
+        .trim()
+        .parse()
+        .map_err(|_| anyhow!("无法识别的快捷键: {}", accelerator))?;
+    if shortcut.mods.is_empty() && !is_function_key(shortcut.key) {
+        bail!(
+            "快捷键 {} 需要包含 Ctrl、Alt、Shift 或 Super 修饰键",
+            accelerator
+        );
+    }
+    Ok(shortcut)
+}
+
+/// 可以绑定快捷键的操作，也可以从托盘菜单触发
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortcutAction {
+    /// 读取剪贴板并在弹窗中翻译
+    ShowPopup,
+    /// 翻译并粘贴替换选中的文本
+    TranslateReplace,
+    /// 翻译并把译文写回剪贴板
+    TranslateCopy,
+    /// 打开翻译历史
+    OpenHistory,
+    /// 切换到下一个预设语言方向
+    ToggleLangPair,
+}
+
+impl ShortcutAction {
+    pub const ALL: [ShortcutAction; 5] = [
+        ShortcutAction::ShowPopup,
+        ShortcutAction::TranslateReplace,
+        ShortcutAction::TranslateCopy,
+        ShortcutAction::OpenHistory,
+        ShortcutAction::ToggleLangPair,
+    ];
+
+    /// 托盘菜单项的 ID
+    pub fn id(self) -> &'static str {
+        match self {
+            ShortcutAction::ShowPopup => "show_popup",
+            ShortcutAction::TranslateReplace => "translate_replace",
+            ShortcutAction::TranslateCopy => "translate_copy",
+            ShortcutAction::OpenHistory => "open_history",
+            ShortcutAction::ToggleLangPair => "toggle_lang_pair",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<ShortcutAction> {
+        Self::ALL.into_iter().find(|action| action.id() == id)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ShortcutAction::ShowPopup => "翻译剪贴板",
+            ShortcutAction::TranslateReplace => "翻译并替换",
+            ShortcutAction::TranslateCopy => "翻译并复制",
+            ShortcutAction::OpenHistory => "翻译历史",
+            ShortcutAction::ToggleLangPair => "切换语言方向",
+        }
+    }
+
+    /// 配置中绑定的快捷键字符串
+    pub fn accelerator(self, config: &ShortcutConfig) -> &str {
+        let accelerator = match self {
+            ShortcutAction::ShowPopup => &config.show_popup,
+            ShortcutAction::TranslateReplace => &config.translate_replace,
+            ShortcutAction::TranslateCopy => &config.translate_copy,
+            ShortcutAction::OpenHistory => &config.open_history,
+            ShortcutAction::ToggleLangPair => &config.toggle_lang_pair,
+        };
+        accelerator.trim()
+    }
+}
+
+/// 校验配置中的快捷键，返回各操作绑定的快捷键，未配置的操作会被跳过
+pub fn parse_config(config: &ShortcutConfig) -> Result<Vec<(ShortcutAction, Shortcut)>> {
+    let mut bindings: Vec<(ShortcutAction, Shortcut)> = Vec::new();
+    for action in ShortcutAction::ALL {
+        let accelerator = action.accelerator(config);
+        if accelerator.is_empty() {
+            continue;
+        }
+        let shortcut = parse(accelerator)?;
+        if let Some((other, _)) = bindings.iter().find(|(_, s)| s.id() == shortcut.id()) {
+            bail!(
+                "快捷键 {} 同时绑定了「{}」和「{}」",
+                accelerator,
+                other.label(),
+                action.label()
+            );
+        }
+        bindings.push((action, shortcut));
+    }
+    Ok(bindings)
+}
+
+/// 查找按下的快捷键对应的操作
+pub fn find_action(config: &ShortcutConfig, shortcut: &Shortcut) -> Option<ShortcutAction> {
+    parse_config(config)
+        .ok()?
+        .into_iter()
+        .find(|(_, s)| s.id() == shortcut.id())
+        .map(|(action, _)| action)
+}
+
+fn is_function_key(key: Code) -> bool {
+    matches!(
+        key,
+        Code::F1
+            | Code::F2
+            | Code::F3
+            | Code::F4
+            | Code::F5
+            | Code::F6
+            | Code::F7
+            | Code::F8
+            | Code::F9
+            | Code::F10
+            | Code::F11
+            | Code::F12
+            | Code::F13
+            | Code::F14
+            | Code::F15
+            | Code::F16
+            | Code::F17
+            | Code::F18
+            | Code::F19
+            | Code::F20
+            | Code::F21
+            | Code::F22
+            | Code::F23
+            | Code::F24
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use tauri_plugin_global_shortcut::Modifiers;
+
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let shortcut = parse("ctrl+shift+T").unwrap();
+        assert_eq!(shortcut.mods, Modifiers::CONTROL | Modifiers::SHIFT);
+        assert_eq!(shortcut.key, Code::KeyT);
+        assert!(parse(" Alt+J ").is_ok());
+        assert!(parse("F8").is_ok());
+
+        assert!(parse("J").is_err());
+        assert!(parse("Ctrl+Foo").is_err());
+        assert!(parse("Ctrl+J+K").is_err());
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let shortcuts = parse_config(&ShortcutConfig::default()).unwrap();
+        assert_eq!(shortcuts.len(), 1);
+        assert_eq!(shortcuts[0].0, ShortcutAction::ShowPopup);
+
+        let config = ShortcutConfig {
+            show_popup: " ".to_string(),
+            translate_copy: "Ctrl+Alt+C".to_string(),
+            ..Default::default()
+        };
+        let shortcuts = parse_config(&config).unwrap();
+        assert_eq!(shortcuts.len(), 1);
+        assert_eq!(
+            find_action(&config, &parse("ctrl+alt+c").unwrap()),
+            Some(ShortcutAction::TranslateCopy)
+        );
+        assert_eq!(find_action(&config, &parse("Ctrl+Alt+J").unwrap()), None);
+
+        let invalid = ShortcutConfig {
+            show_popup: "Ctrl+".to_string(),
+            ..Default::default()
+        };
+        assert!(parse_config(&invalid).is_err());
+        // 同一个快捷键不能绑定多个操作
+        let duplicated = ShortcutConfig {
+            show_popup: "Ctrl+Alt+J".to_string(),
+            open_history: "Alt+Ctrl+J".to_string(),
+            ..Default::default()
+        };
+        assert!(parse_config(&duplicated).is_err());
+    }
+
+    #[test]
+    fn test_action_id() {
+        for action in ShortcutAction::ALL {
+            assert_eq!(ShortcutAction::from_id(action.id()), Some(action));
+        }
+        assert_eq!(ShortcutAction::from_id("quit"), None);
+    }
+}
@@ -0,0 +1,64 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use tokio::sync::OnceCell;
+
+use crate::config::{self, AppConfig};
+use crate::database::Database;
+
+/// 应用全局状态，通过 `app.manage` 交给 Tauri 托管
+pub struct AppState {
+    config: Mutex<AppConfig>,
+    config_path: PathBuf,
+    db_path: PathBuf,
+    database: OnceCell<Database>,
+    started_at: DateTime<Utc>,
+}
+
+impl AppState {
+    pub fn new(config: AppConfig, config_path: PathBuf, db_path: PathBuf) -> Self {
+        Self {
+            config: Mutex::new(config),
+            config_path,
+            db_path,
+            database: OnceCell::new(),
+            started_at: Utc::now(),
+        }
+    }
+
+    // 应用启动时间，用于区分本次使用产生的翻译记录
+    pub fn started_at(&self) -> DateTime<Utc> {
+        self.started_at
+    }
+
+    // 获取当前配置的副本
+    pub fn config(&self) -> AppConfig {
+        self.config.lock().expect("Config lock failed").clone()
+    }
+
+    // 写入配置文件并更新内存中的配置
+    pub fn update_config(&self, new_config: AppConfig) -> Result<()> {
+        config::save_config(&self.config_path, &new_config)?;
+        *self.config.lock().expect("Config lock failed") = new_config;
+        Ok(())
+    }
+
+    /// 获取数据库实例
+    ///
+    /// 数据库在首次调用时初始化，初始化完成前的并发调用会等待同一次初始化，
+    /// 初始化失败时下一次调用会重新尝试。
+    pub async fn database(&self) -> Result<&Database, sqlx::Error> {
+        self.database
+            .get_or_try_init(|| Database::new(self.db_path.clone()))
+            .await
+    }
+
+    /// 获取数据库实例，初始化失败时返回命令可以直接返回给前端的错误信息
+    pub async fn command_database(&self) -> Result<&Database, String> {
+        self.database()
+            .await
+            .map_err(|e| format!("数据库初始化失败: {}", e))
+    }
+}
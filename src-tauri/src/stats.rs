@@ -0,0 +1,160 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::ModelPrice;
+
+/// 用量统计的分组方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StatsGroupBy {
+    Day,
+    Week,
+    Month,
+    Platform,
+    Model,
+    LangPair,
+    Total,
+}
+
+impl StatsGroupBy {
+    // 对应分组键的 SQL 表达式，created_at 为 RFC 3339 格式的 UTC 时间，按 UTC 日期分组
+    pub fn bucket_expr(self) -> &'static str {
+        match self {
+            StatsGroupBy::Day => "substr(created_at, 1, 10)",
+            // ISO 周：按所在周的星期四确定年份和周数，跨年的一周归入同一组
+            StatsGroupBy::Week => {
+                "printf('%s-W%02d', strftime('%Y', created_at, '-3 days', 'weekday 4'), \
+                    (strftime('%j', created_at, '-3 days', 'weekday 4') - 1) / 7 + 1)"
+            }
+            StatsGroupBy::Month => "substr(created_at, 1, 7)",
+            StatsGroupBy::Platform => "COALESCE(platform, 'unknown')",
+            StatsGroupBy::Model => "COALESCE(model_name, platform, 'unknown')",
+            StatsGroupBy::LangPair => "source_lang || '->' || target_lang",
+            StatsGroupBy::Total => "'total'",
+        }
+    }
+}
+
+/// 数据库按分组键、平台和模型汇总的一行用量
+#[derive(Debug, Clone)]
+pub struct UsageRow {
+    pub bucket: String,
+    pub platform: Option<String>,
+    pub model_name: Option<String>,
+    pub translations: i64,
+    pub characters: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub duration_sum_ms: i64,
+    pub duration_count: i64,
+}
+
+/// 单个分组的用量统计
+#[derive(Debug, Serialize, Clone, Default, PartialEq)]
+pub struct UsageStat {
+    pub key: String,
+    pub translations: i64,
+    pub characters: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub avg_duration_ms: Option<f64>,
+    pub estimated_cost: f64,
+}
+
+/// 合并同一分组键下的用量并按模型价格估算费用
+///
+/// 价格先按模型名称查找，找不到时使用平台名称，都没有配置时费用记为 0。
+pub fn aggregate(rows: Vec<UsageRow>, prices: &HashMap<String, ModelPrice>) -> Vec<UsageStat> {
+    let mut buckets: BTreeMap<String, (UsageStat, i64, i64)> = BTreeMap::new();
+
+    for row in rows {
+        let price = row
+            .model_name
+            .as_ref()
+            .and_then(|model| prices.get(model))
+            .or_else(|| row.platform.as_ref().and_then(|p| prices.get(p)))
+            .copied()
+            .unwrap_or_default();
+
+        let (stat, duration_sum, duration_count) =
+            buckets.entry(row.bucket.clone()).or_insert_with(|| {
+                (
+                    UsageStat {
+                        key: row.bucket.clone(),
+                        ..Default::default()
+                    },
+                    0,
+                    0,
+                )
+            });
+        stat.translations += row.translations;
+        stat.characters += row.characters;
+        stat.prompt_tokens += row.prompt_tokens;
+        stat.completion_tokens += row.completion_tokens;
+        stat.estimated_cost += (row.prompt_tokens as f64 * price.input_per_million
+            + row.completion_tokens as f64 * price.output_per_million)
+            / 1_000_000.0;
+        *duration_sum += row.duration_sum_ms;
+        *duration_count += row.duration_count;
+    }
+
+    buckets
+        .into_values()
+        .map(|(mut stat, duration_sum, duration_count)| {
+            if duration_count > 0 {
+                stat.avg_duration_ms = Some(duration_sum as f64 / duration_count as f64);
+            }
+            stat
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(bucket: &str, model: Option<&str>, prompt: i64, completion: i64) -> UsageRow {
+        UsageRow {
+            bucket: bucket.to_string(),
+            platform: Some("DeepSeek".to_string()),
+            model_name: model.map(str::to_string),
+            translations: 2,
+            characters: 100,
+            prompt_tokens: prompt,
+            completion_tokens: completion,
+            duration_sum_ms: 600,
+            duration_count: 2,
+        }
+    }
+
+    #[test]
+    fn test_aggregate_merges_models_and_prices() {
+        let mut prices = HashMap::new();
+        prices.insert(
+            "deepseek-chat".to_string(),
+            ModelPrice {
+                input_per_million: 1.0,
+                output_per_million: 2.0,
+            },
+        );
+
+        let stats = aggregate(
+            vec![
+                row("2025-06-01", Some("deepseek-chat"), 1_000_000, 500_000),
+                row("2025-06-01", Some("unpriced"), 1_000, 1_000),
+                row("2025-06-02", None, 0, 0),
+            ],
+            &prices,
+        );
+
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].key, "2025-06-01");
+        assert_eq!(stats[0].translations, 4);
+        assert_eq!(stats[0].characters, 200);
+        assert_eq!(stats[0].prompt_tokens, 1_001_000);
+        assert_eq!(stats[0].avg_duration_ms, Some(300.0));
+        assert!((stats[0].estimated_cost - 2.0).abs() < f64::EPSILON);
+        assert_eq!(stats[1].estimated_cost, 0.0);
+    }
+}
@@ -1,36 +1,336 @@
+use std::sync::Mutex;
+
+use anyhow::anyhow;
 use tauri::{
-    menu::{Menu, MenuItem},
+    menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::TrayIconBuilder,
-    AppHandle, Manager,
+    AppHandle, Emitter, Manager, Wry,
 };
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::app_window::{self, AppWindow};
+use crate::config::AppConfig;
+use crate::database::TranslationRecord;
+use crate::lang::Lang;
+use crate::shortcut::ShortcutAction;
+use crate::state::AppState;
+use crate::{actions, clipboard_watch, utils};
+
+const TRAY_ID: &str = "main";
+// 托盘菜单中显示的最近翻译条数
+const RECENT_COUNT: i32 = 5;
+// 最近翻译菜单项显示的最大字符数
+const RECENT_LABEL_CHARS: usize = 24;
+
+/// 托盘菜单状态，保存菜单中显示的最近翻译，点击时复制对应的译文
+#[derive(Default)]
+pub struct TrayState {
+    recent: Mutex<Vec<TranslationRecord>>,
+}
+
+// 带参数的菜单项，ID 格式为 `前缀:参数`
+#[derive(Debug, PartialEq)]
+enum MenuCommand<'a> {
+    SourceLang(Lang),
+    TargetLang(Lang),
+    Profile(&'a str),
+    Recent(&'a str),
+}
+
+impl<'a> MenuCommand<'a> {
+    fn id(&self) -> String {
+        match self {
+            MenuCommand::SourceLang(lang) => format!("source:{}", <&str>::from(*lang)),
+            MenuCommand::TargetLang(lang) => format!("target:{}", <&str>::from(*lang)),
+            MenuCommand::Profile(name) => format!("profile:{}", name),
+            MenuCommand::Recent(id) => format!("recent:{}", id),
+        }
+    }
+
+    fn parse(id: &'a str) -> Option<Self> {
+        let (prefix, value) = id.split_once(':')?;
+        match prefix {
+            "source" => Lang::from_code(value).map(MenuCommand::SourceLang),
+            "target" => Lang::from_code(value)
+                .filter(|lang| *lang != Lang::Auto)
+                .map(MenuCommand::TargetLang),
+            "profile" => Some(MenuCommand::Profile(value)),
+            "recent" => Some(MenuCommand::Recent(value)),
+            _ => None,
+        }
+    }
+}
 
 pub fn setup_tray_icon(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-    let quit_i = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-    let show_i = MenuItem::with_id(app, "show", "Show", true, None::<&str>)?;
-    let menu = Menu::with_items(app, &[&show_i, &quit_i])?;
-    let _tray = TrayIconBuilder::new()
+    app.manage(TrayState::default());
+    let menu = build_menu(app, &app.state::<AppState>().config(), &[])?;
+    let _tray = TrayIconBuilder::with_id(TRAY_ID)
         .menu(&menu)
         .show_menu_on_left_click(true)
         .icon(app.default_window_icon().unwrap().clone())
-        .on_menu_event(|app, event| match event.id.as_ref() {
-            "quit" => {
-                println!("quit menu item was clicked");
-                app.exit(0);
+        .on_menu_event(|app, event| handle_menu_event(app, event.id.as_ref()))
+        .build(app)?;
+    // 最近翻译需要从数据库读取，托盘图标创建后再加载
+    refresh(app);
+    Ok(())
+}
+
+/// 按当前配置和最近的翻译记录重建托盘菜单，配置或翻译记录变化后调用
+pub fn refresh(app_handle: &AppHandle) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = rebuild_menu(&app_handle).await {
+            eprintln!("更新托盘菜单失败: {}", e);
+        }
+    });
+}
+
+async fn rebuild_menu(app_handle: &AppHandle) -> anyhow::Result<()> {
+    let state = app_handle.state::<AppState>();
+    let recent = match state.database().await {
+        Ok(database) => {
+            database
+                .get_translation_history(Some(RECENT_COUNT), None)
+                .await?
+        }
+        Err(e) => {
+            eprintln!("数据库初始化失败: {}", e);
+            Vec::new()
+        }
+    };
+    let menu = build_menu(app_handle, &state.config(), &recent)?;
+    *app_handle
+        .state::<TrayState>()
+        .recent
+        .lock()
+        .expect("Tray state lock failed") = recent;
+    if let Some(tray) = app_handle.tray_by_id(TRAY_ID) {
+        tray.set_menu(Some(menu))?;
+    }
+    Ok(())
+}
+
+fn build_menu(
+    app: &AppHandle,
+    config: &AppConfig,
+    recent: &[TranslationRecord],
+) -> tauri::Result<Menu<Wry>> {
+    let quit_i = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+    let show_i = MenuItem::with_id(app, "show", "Show", true, None::<&str>)?;
+    // 快捷键可以触发的操作也可以从托盘菜单执行
+    let action_items = ShortcutAction::ALL
+        .into_iter()
+        .map(|action| MenuItem::with_id(app, action.id(), action.label(), true, None::<&str>))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let source_items = Lang::ALL
+        .into_iter()
+        .map(|lang| {
+            let id = MenuCommand::SourceLang(lang).id();
+            let checked = config.lang_pair.source == lang;
+            CheckMenuItem::with_id(app, id, lang.label(), true, checked, None::<&str>)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let target_items = Lang::ALL
+        .into_iter()
+        .filter(|lang| *lang != Lang::Auto)
+        .map(|lang| {
+            let id = MenuCommand::TargetLang(lang).id();
+            let checked = config.lang_pair.target == lang;
+            CheckMenuItem::with_id(app, id, lang.label(), true, checked, None::<&str>)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let profile_items = config
+        .all_profiles()
+        .iter()
+        .map(|profile| {
+            let id = MenuCommand::Profile(&profile.name).id();
+            let checked = config.provider.name == profile.name;
+            CheckMenuItem::with_id(app, id, &profile.name, true, checked, None::<&str>)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut recent_items = recent
+        .iter()
+        .map(|record| {
+            let id = MenuCommand::Recent(&record.id).id();
+            let label = utils::truncate(&record.translated_text, RECENT_LABEL_CHARS);
+            MenuItem::with_id(app, id, label, true, None::<&str>)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if recent_items.is_empty() {
+        recent_items.push(MenuItem::new(app, "暂无翻译记录", false, None::<&str>)?);
+    }
+
+    let source_menu = submenu(app, "源语言", &source_items)?;
+    let target_menu = submenu(app, "目标语言", &target_items)?;
+    let profile_menu = submenu(app, "翻译服务", &profile_items)?;
+    let recent_menu = submenu(app, "最近翻译（点击复制）", &recent_items)?;
+    let watch_i = CheckMenuItem::with_id(
+        app,
+        "clipboard_watch",
+        "监听剪贴板",
+        true,
+        config.clipboard_watch.enabled,
+        None::<&str>,
+    )?;
+    let auto_hide_i = CheckMenuItem::with_id(
+        app,
+        "auto_hide",
+        "失去焦点时隐藏窗口",
+        true,
+        config.auto_hide,
+        None::<&str>,
+    )?;
+    let settings_i = MenuItem::with_id(app, "open_settings", "设置", true, None::<&str>)?;
+
+    let mut items: Vec<&dyn IsMenuItem<Wry>> = vec![&show_i];
+    items.extend(action_items.iter().map(|item| item as &dyn IsMenuItem<Wry>));
+    let separators = [
+        PredefinedMenuItem::separator(app)?,
+        PredefinedMenuItem::separator(app)?,
+        PredefinedMenuItem::separator(app)?,
+    ];
+    items.extend([
+        &separators[0] as &dyn IsMenuItem<Wry>,
+        &source_menu,
+        &target_menu,
+        &profile_menu,
+        &recent_menu,
+        &separators[1],
+        &watch_i,
+        &auto_hide_i,
+        &settings_i,
+        &separators[2],
+        &quit_i,
+    ]);
+    Menu::with_items(app, &items)
+}
+
+fn submenu<M: IsMenuItem<Wry>>(
+    app: &AppHandle,
+    text: &str,
+    items: &[M],
+) -> tauri::Result<Submenu<Wry>> {
+    let items: Vec<&dyn IsMenuItem<Wry>> = items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<Wry>)
+        .collect();
+    Submenu::with_items(app, text, true, &items)
+}
+
+fn handle_menu_event(app: &AppHandle, id: &str) {
+    let result = match id {
+        "quit" => {
+            println!("quit menu item was clicked");
+            app_window::save_all(app);
+            app.exit(0);
+            Ok(())
+        }
+        "show" => {
+            println!("show menu item was clicked");
+            let main_window = app.get_webview_window("main");
+            if let Some(window) = main_window {
+                window
+                    .show()
+                    .unwrap_or_else(|e| eprintln!("显示窗口失败: {}", e));
+                window
+                    .set_focus()
+                    .unwrap_or_else(|e| eprintln!("窗口聚焦失败: {}", e));
+            } else {
+                println!("main window not found");
             }
-            "show" => {
-                println!("show menu item was clicked");
-                let main_window = app.get_webview_window("main");
-                if let Some(window) = main_window {
-                    window.show().unwrap();
-                    window.set_focus().unwrap();
-                } else {
-                    println!("main window not found");
-                }
+            Ok(())
+        }
+        "clipboard_watch" => {
+            let enabled = app.state::<AppState>().config().clipboard_watch.enabled;
+            clipboard_watch::set_enabled(app, !enabled)
+        }
+        "auto_hide" => update_config(app, |config| {
+            config.auto_hide = !config.auto_hide;
+            Ok(())
+        }),
+        "open_settings" => app_window::open(app, AppWindow::Settings),
+        id => {
+            if let Some(action) = ShortcutAction::from_id(id) {
+                actions::run(app, action);
+                return;
             }
-            _ => {
-                println!("menu item with id: {} was clicked", event.id.as_ref())
+            match MenuCommand::parse(id) {
+                Some(command) => run_command(app, command),
+                None => {
+                    println!("menu item with id: {} was clicked", id);
+                    return;
+                }
             }
-        })
-        .build(app)?;
+        }
+    };
+    if let Err(e) = result {
+        eprintln!("托盘菜单操作失败: {}", e);
+    }
+    // 点击勾选项时系统会切换勾选状态，操作失败时也需要按配置重建菜单
+    refresh(app);
+}
+
+fn run_command(app: &AppHandle, command: MenuCommand) -> anyhow::Result<()> {
+    match command {
+        MenuCommand::SourceLang(lang) => set_lang(app, |config| config.lang_pair.source = lang),
+        MenuCommand::TargetLang(lang) => set_lang(app, |config| config.lang_pair.target = lang),
+        MenuCommand::Profile(name) => update_config(app, |config| config.switch_profile(name)),
+        MenuCommand::Recent(id) => {
+            let text = app
+                .state::<TrayState>()
+                .recent
+                .lock()
+                .expect("Tray state lock failed")
+                .iter()
+                .find(|record| record.id == id)
+                .map(|record| record.translated_text.clone())
+                .ok_or_else(|| anyhow!("翻译记录不存在"))?;
+            clipboard_watch::ignore(app, &text);
+            app.clipboard().write_text(text)?;
+            Ok(())
+        }
+    }
+}
+
+// 修改语言方向后通知前端更新语言选择
+fn set_lang(app: &AppHandle, change: impl FnOnce(&mut AppConfig)) -> anyhow::Result<()> {
+    update_config(app, |config| {
+        change(config);
+        Ok(())
+    })?;
+    let lang_pair = app.state::<AppState>().config().lang_pair;
+    app.emit("lang-pair-changed", lang_pair)?;
     Ok(())
 }
+
+fn update_config(
+    app: &AppHandle,
+    change: impl FnOnce(&mut AppConfig) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let state = app.state::<AppState>();
+    let mut config = state.config();
+    change(&mut config)?;
+    state.update_config(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_menu_command() {
+        let commands = [
+            MenuCommand::SourceLang(Lang::Auto),
+            MenuCommand::TargetLang(Lang::Ja),
+            MenuCommand::Profile("deepl:free"),
+            MenuCommand::Recent("d3b07384"),
+        ];
+        for command in commands {
+            assert_eq!(MenuCommand::parse(&command.id()), Some(command));
+        }
+        assert_eq!(MenuCommand::parse("target:auto"), None);
+        assert_eq!(MenuCommand::parse("source:fr"), None);
+        assert_eq!(MenuCommand::parse("quit"), None);
+    }
+}
@@ -1,48 +1,55 @@
-use enigo::Mouse;
-use enigo::{Enigo, Settings};
-use tauri::Monitor;
+use enigo::{Direction, Enigo, Key, Keyboard, Settings};
 
-use crate::config;
-
-/**
- * 根据鼠标位置和缩放因子计算窗口位置
- */
-pub fn calculate_window_position(primary_monitor: Monitor, scale_factor: f64) -> (u32, u32) {
-    let enigo = Enigo::new(&Settings::default()).expect("Failed to create Enigo instance");
-
-    let (mouse_x, mouse_y) = enigo.location().expect("Failed to get mouse position");
-
-    println!(
-        "Mouse position: ({}, {}), scale_factor: {}",
-        mouse_x, mouse_y, scale_factor
-    );
-
-    // 简单的偏移量，例如在鼠标位置右下角 +10 像素
-    let target_x = mouse_x as f64 * scale_factor + 10.0 * scale_factor;
-    let target_y = mouse_y as f64 * scale_factor + 10.0 * scale_factor;
+/// 截取文本的第一行，超过 `max_chars` 个字符时截断并添加省略号
+pub fn truncate(text: &str, max_chars: usize) -> String {
+    let line = text.trim().lines().next().unwrap_or_default();
+    if line.chars().count() <= max_chars && line.len() == text.trim().len() {
+        return line.to_string();
+    }
+    let mut truncated: String = line.chars().take(max_chars).collect();
+    truncated.push('…');
+    truncated
+}
 
-    // 考虑屏幕边缘，防止窗口超出屏幕 (可选但推荐)
-    // 需要获取屏幕信息， Tauri 2.0 有 Monitor API
+/// 模拟复制快捷键，macOS 上为 Command+C，其他系统上为 Ctrl+C
+pub fn simulate_copy() -> anyhow::Result<()> {
+    simulate_shortcut('c')
+}
 
-    let monitor_size = primary_monitor.size(); // Width and height in logical pixels
-    let monitor_position = primary_monitor.position(); // Top-left corner position
+/// 模拟粘贴快捷键，macOS 上为 Command+V，其他系统上为 Ctrl+V
+pub fn simulate_paste() -> anyhow::Result<()> {
+    simulate_shortcut('v')
+}
 
-    let mut final_x = target_x;
-    let mut final_y = target_y;
+// 模拟“修饰键+字母”，先松开触发全局快捷键时可能仍按着的修饰键，避免组合成其他快捷键
+fn simulate_shortcut(key: char) -> anyhow::Result<()> {
+    #[cfg(target_os = "macos")]
+    let modifier = Key::Meta;
+    #[cfg(not(target_os = "macos"))]
+    let modifier = Key::Control;
 
-    // 简单的边缘检查 (需要 monitor size 和 position)
-    if target_x + config::INIT_WEIDTH * scale_factor
-        > (monitor_position.x as u32 + monitor_size.width) as f64
-    {
-        final_x = target_x - config::INIT_WEIDTH * scale_factor - 20.0 * scale_factor;
-        // 显示在左侧
+    let mut enigo = Enigo::new(&Settings::default())?;
+    for held in [Key::Shift, Key::Alt, Key::Control, Key::Meta] {
+        enigo.key(held, Direction::Release)?;
     }
-    if target_y + config::INIT_HEIGHT * scale_factor
-        > (monitor_position.y as u32 + monitor_size.height) as f64
-    {
-        final_y = target_y - config::INIT_HEIGHT * scale_factor - 20.0 * scale_factor;
-        // 显示在上方
+    enigo.key(modifier, Direction::Press)?;
+    let result = enigo.key(Key::Unicode(key), Direction::Click);
+    // 无论是否成功都要松开修饰键
+    enigo.key(modifier, Direction::Release)?;
+    result?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate(" hello ", 5), "hello");
+        assert_eq!(truncate("hello world", 5), "hello…");
+        assert_eq!(truncate("你好世界", 2), "你好…");
+        assert_eq!(truncate("first\nsecond", 10), "first…");
+        assert_eq!(truncate("", 5), "");
     }
-    println!("Final position: ({}, {})", final_x, final_y);
-    (final_x as u32, final_y as u32)
 }
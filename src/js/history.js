@@ -9,6 +9,13 @@ let currentSearchQuery = '';
 let totalPages = 0;
 let allHistory = [];
 
+// 显示历史页面，已显示时不做处理
+export function showHistoryPage() {
+    if (!isHistoryVisible) {
+        toggleHistoryPage();
+    }
+}
+
 // 切换历史页面显示
 export function toggleHistoryPage() {
     console.log('切换历史页面');
@@ -93,7 +100,9 @@ function displayHistory() {
     if (isSearchMode && currentSearchQuery) {
         filteredHistory = allHistory.filter(item => 
             item.source_text.toLowerCase().includes(currentSearchQuery.toLowerCase()) ||
-            item.translated_text.toLowerCase().includes(currentSearchQuery.toLowerCase())
+            item.translated_text.toLowerCase().includes(currentSearchQuery.toLowerCase()) ||
+            (item.platform || '').toLowerCase().includes(currentSearchQuery.toLowerCase()) ||
+            (item.model_name || '').toLowerCase().includes(currentSearchQuery.toLowerCase())
         );
     }
     
@@ -127,10 +136,12 @@ function createHistoryItem(item) {
     div.className = 'history-item';
     
     const time = new Date(item.created_at).toLocaleString('zh-CN');
+    const meta = formatHistoryMeta(item);
     
     div.innerHTML = `
         <div class="history-item-header">
             <span class="history-item-time">${time}</span>
+            <span class="history-item-meta">${escapeHtml(meta)}</span>
             <button class="history-item-delete" onclick="deleteHistoryItem('${item.id}')">
                 删除
             </button>
@@ -150,6 +161,19 @@ function createHistoryItem(item) {
     return div;
 }
 
+// 格式化平台、模型、耗时和 token 用量
+function formatHistoryMeta(item) {
+    const parts = [];
+    if (item.instruction) parts.push(`修订: ${item.instruction}`);
+    if (item.platform) parts.push(item.platform);
+    if (item.model_name) parts.push(item.model_name);
+    if (item.duration_ms != null) parts.push(`${item.duration_ms}ms`);
+    if (item.prompt_tokens != null || item.completion_tokens != null) {
+        parts.push(`${item.prompt_tokens ?? '-'}/${item.completion_tokens ?? '-'} tokens`);
+    }
+    return parts.join(' · ');
+}
+
 // 删除历史记录项
 export async function deleteHistoryItem(id) {
     try {
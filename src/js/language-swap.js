@@ -1,3 +1,4 @@
+import { invoke } from './tauri-api.js';
 import { updateSourcePlaceholder, updateTargetPlaceholder } from './ui-utils.js';
 import { translateText } from './translation.js';
 
@@ -16,10 +17,44 @@ function swapLanguages() {
     
     updateSourcePlaceholder();
     updateTargetPlaceholder();
+    saveLangPair();
     
     if (window.sourceText.value.trim()) {
         translateText();
     }
 }
 
-export { swapLanguages };
\ No newline at end of file
+// 显示配置中的语言方向
+function applyLangPair(langPair) {
+    if (!langPair || !window.targetLang) return;
+    window.sourceLang.value = langPair.source;
+    window.targetLang.value = langPair.target;
+    updateSourcePlaceholder();
+    updateTargetPlaceholder();
+}
+
+// 加载配置中的语言方向
+async function loadLangPair() {
+    try {
+        const result = await invoke('load_config');
+        if (result.code === 0 && result.data) {
+            applyLangPair(result.data.langPair);
+        }
+    } catch (error) {
+        console.error('加载语言方向失败:', error);
+    }
+}
+
+// 保存当前语言方向，快捷键和托盘菜单触发的翻译会使用该方向
+async function saveLangPair() {
+    if (!window.targetLang) return;
+    try {
+        await invoke('set_lang_pair', {
+            langPair: { source: window.sourceLang.value, target: window.targetLang.value }
+        });
+    } catch (error) {
+        console.error('保存语言方向失败:', error);
+    }
+}
+
+export { applyLangPair, loadLangPair, saveLangPair, swapLanguages };
\ No newline at end of file
@@ -1,9 +1,12 @@
-import { invoke, initializeTauri } from './tauri-api.js';
-import { handleTextInput } from './translation.js';
+import { invoke, initializeTauri, listen } from './tauri-api.js';
+import { handleTextInput, refineTranslation, showTranslationResult } from './translation.js';
 import { setupAutoResize, updateSourcePlaceholder, updateTargetPlaceholder } from './ui-utils.js';
-import { swapLanguages } from './language-swap.js';
-import { toggleSettingsPage, saveSettings, resetSettings, togglePasswordVisibility } from './settings.js';
-import { toggleHistoryPage, initializeHistoryEvents } from './history.js';
+import { applyLangPair, loadLangPair, saveLangPair, swapLanguages } from './language-swap.js';
+import { showSettingsPage, saveSettings, resetSettings, togglePasswordVisibility, loadModelOptions, pullModel, importDictionary } from './settings.js';
+import { showHistoryPage, initializeHistoryEvents } from './history.js';
+
+// 历史和设置窗口与主窗口使用同一个页面，通过 URL 中的 #history、#settings 区分
+const windowView = window.location.hash.slice(1);
 
 // 全局元素引用
 window.sourceLang = document.getElementById('sourceLang');
@@ -15,6 +18,7 @@ window.translatedText = document.getElementById('translatedText');
 document.addEventListener('DOMContentLoaded', async () => {
     // 加载并应用主题
     await loadAndApplyTheme();
+    await loadLangPair();
     
     // 初始化占位符
     updateSourcePlaceholder();
@@ -25,6 +29,17 @@ document.addEventListener('DOMContentLoaded', async () => {
         window.sourceText.addEventListener('input', handleTextInput);
         window.handleTextInput = handleTextInput; // 暴露给全局
     }
+
+    const refineInput = document.getElementById('refineInput');
+    if (refineInput) {
+        refineInput.addEventListener('keydown', async (event) => {
+            if (event.key === 'Enter' && refineInput.value.trim()) {
+                const instruction = refineInput.value.trim();
+                refineInput.value = '';
+                await refineTranslation(instruction);
+            }
+        });
+    }
     
     // 绑定语言交换
     const swapBtn = document.querySelector('.swap-btn');
@@ -44,16 +59,21 @@ document.addEventListener('DOMContentLoaded', async () => {
         });
     }
     
-    // 绑定设置相关事件
+    // 固定窗口后失去焦点时不隐藏，并可以调整大小
+    const pinIcon = document.querySelector('.pin-icon');
+    if (pinIcon) {
+        pinIcon.addEventListener('click', togglePinned);
+    }
+    
+    // 设置和历史在独立窗口中打开
     const settingsIcon = document.querySelector('.settings-icon');
     if (settingsIcon) {
-        settingsIcon.addEventListener('click', toggleSettingsPage);
+        settingsIcon.addEventListener('click', () => openWindow('settings'));
     }
     
-    // 绑定历史按钮事件
     const historyIcon = document.querySelector('.history-icon');
     if (historyIcon) {
-        historyIcon.addEventListener('click', toggleHistoryPage);
+        historyIcon.addEventListener('click', () => openWindow('history'));
     }
     
     const saveBtn = document.getElementById('saveBtn');
@@ -70,16 +90,39 @@ document.addEventListener('DOMContentLoaded', async () => {
     if (toggleApiKeyBtn) {
         toggleApiKeyBtn.addEventListener('click', togglePasswordVisibility);
     }
+
+    const pullModelBtn = document.getElementById('pullModelBtn');
+    if (pullModelBtn) {
+        pullModelBtn.addEventListener('click', pullModel);
+    }
+
+    const importDictionaryBtn = document.getElementById('importDictionaryBtn');
+    if (importDictionaryBtn) {
+        importDictionaryBtn.addEventListener('click', importDictionary);
+    }
+
+    // 修改接口地址或平台后刷新模型列表
+    const apiUrlInput = document.getElementById('apiUrl');
+    if (apiUrlInput) {
+        apiUrlInput.addEventListener('change', loadModelOptions);
+    }
+    document.querySelectorAll('input[name="platform"]').forEach(radio => {
+        radio.addEventListener('change', loadModelOptions);
+    });
     
     // 绑定语言选择变化事件
     if (window.sourceLang) {
         window.sourceLang.addEventListener('change', () => {
             updateSourcePlaceholder();
+            saveLangPair();
             if (window.sourceText.value.trim()) {
                 handleTextInput();
             }
         });
     }
+    if (window.targetLang) {
+        window.targetLang.addEventListener('change', saveLangPair);
+    }
     
     // 设置自动调整高度
     setupAutoResize();
@@ -89,8 +132,53 @@ document.addEventListener('DOMContentLoaded', async () => {
     
     // 初始化Tauri功能
     await initializeTauri();
+
+    // 快捷键或托盘菜单触发的操作
+    await listen('lang-pair-changed', (event) => applyLangPair(event.payload));
+    // 其他窗口或托盘菜单修改配置后更新主题
+    await listen('config-updated', loadAndApplyTheme);
+    // 点击系统通知后显示完整的原文和译文
+    await listen('show-translation', (event) => showTranslationResult(event.payload));
+
+    if (windowView === 'history') {
+        document.body.classList.add('standalone-window');
+        showHistoryPage();
+    } else if (windowView === 'settings') {
+        document.body.classList.add('standalone-window');
+        await showSettingsPage();
+    }
 });
 
+// 固定或取消固定主窗口
+async function togglePinned() {
+    const pinned = !document.body.classList.contains('pinned');
+    try {
+        const result = await invoke('set_pinned', { pinned });
+        if (result.code === 0) {
+            document.body.classList.toggle('pinned', pinned);
+            document.querySelector('.pin-icon').classList.toggle('active', pinned);
+        } else {
+            alert(result.msg);
+        }
+    } catch (error) {
+        console.error('固定窗口失败:', error);
+        alert(error.msg || error);
+    }
+}
+
+// 打开历史或设置窗口，已打开时聚焦
+async function openWindow(label) {
+    try {
+        const result = await invoke('open_window', { window: label });
+        if (result.code !== 0) {
+            alert(result.msg);
+        }
+    } catch (error) {
+        console.error('打开窗口失败:', error);
+        alert(error.msg || error);
+    }
+}
+
 // 加载并应用主题
 async function loadAndApplyTheme() {
     try {
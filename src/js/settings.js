@@ -1,4 +1,4 @@
-import { invoke } from './tauri-api.js';
+import { invoke, listen } from './tauri-api.js';
 
 // 设置状态
 let isSettingsVisible = false;
@@ -23,6 +23,13 @@ async function toggleSettingsPage() {
     }
 }
 
+// 显示设置页面，已显示时不做处理
+async function showSettingsPage() {
+    if (!isSettingsVisible) {
+        await toggleSettingsPage();
+    }
+}
+
 // 加载设置
 async function loadSettings() {
     const settings = await getStoredSettings();
@@ -31,9 +38,15 @@ async function loadSettings() {
     document.getElementById('modelName').value = settings.modelName || '';
     document.getElementById('systemPrompt').value = settings.systemPrompt || '';
     document.getElementById('prompt').value = settings.prompt || '';
+    document.querySelectorAll('input[data-shortcut]').forEach(input => {
+        input.value = settings.shortcuts?.[input.dataset.shortcut] ?? '';
+    });
     document.querySelectorAll('input[name="platform"]').forEach(radio => {
         radio.checked = radio.value === settings.platform;
     });
+    setModelStatus('');
+    loadModelOptions();
+    loadDictionaries();
 
      // 加载主题设置
      const theme = settings.theme || 'Dark';
@@ -89,14 +102,18 @@ async function saveSettings() {
         return;
     }
     
+    // 保留设置页面之外的配置项（如模型价格、翻译服务列表）
+    const storedSettings = await getStoredSettings();
     const settings = {
+        ...storedSettings,
         apiKey,
         apiUrl,
         platform: selectedPlatform.value,
         modelName,
         systemPrompt,
         prompt,
-        theme: selectedTheme.value
+        theme: selectedTheme.value,
+        shortcuts: getFormShortcuts(storedSettings.shortcuts)
     };
     
     try {
@@ -105,6 +122,11 @@ async function saveSettings() {
             console.log('保存配置成功');
             // 应用新主题
             applyTheme(selectedTheme.value);
+            // 独立的设置窗口保存成功后直接关闭
+            if (document.body.classList.contains('standalone-window')) {
+                await invoke('close_window');
+                return;
+            }
             // 保存成功后关闭设置页面
             const settingsPage = document.getElementById('settingsPage');
             const translationPage = document.getElementById('translationPage');
@@ -120,7 +142,156 @@ async function saveSettings() {
         }
     } catch (error) {
         console.error('保存设置失败:', error);
-        alert('保存设置失败');
+        alert(error.msg || '保存设置失败');
+    }
+}
+
+// 表单中的全局快捷键，未在设置页显示的快捷键保持不变
+function getFormShortcuts(storedShortcuts) {
+    const shortcuts = { ...storedShortcuts };
+    document.querySelectorAll('input[data-shortcut]').forEach(input => {
+        shortcuts[input.dataset.shortcut] = input.value.trim();
+    });
+    return shortcuts;
+}
+
+// 当前表单中的翻译服务配置，未保存的接口地址也可以用来查询模型
+async function getFormProfile() {
+    const storedSettings = await getStoredSettings();
+    const selectedPlatform = document.querySelector('input[name="platform"]:checked');
+    return {
+        ...storedSettings,
+        apiKey: document.getElementById('apiKey').value.trim(),
+        apiUrl: document.getElementById('apiUrl').value.trim(),
+        platform: selectedPlatform ? selectedPlatform.value : storedSettings.platform,
+        modelName: document.getElementById('modelName').value.trim()
+    };
+}
+
+function setModelStatus(text) {
+    const status = document.getElementById('modelStatus');
+    if (status) {
+        status.textContent = text;
+    }
+}
+
+// 加载模型下拉列表，不支持的平台保持手动输入
+async function loadModelOptions() {
+    const datalist = document.getElementById('modelOptions');
+    if (!datalist) {
+        return;
+    }
+    try {
+        const result = await invoke('list_models', { profile: await getFormProfile() });
+        datalist.innerHTML = '';
+        if (result && result.code === 0) {
+            result.data.forEach(model => {
+                const option = document.createElement('option');
+                option.value = model.name;
+                if (model.parameterSize) {
+                    option.label = `${model.name} (${model.parameterSize})`;
+                }
+                datalist.appendChild(option);
+            });
+        }
+    } catch (error) {
+        datalist.innerHTML = '';
+        console.warn('获取模型列表失败:', error);
+    }
+}
+
+// 拉取 Ollama 模型并显示下载进度
+async function pullModel() {
+    const profile = await getFormProfile();
+    if (profile.platform !== 'OLLama' || !profile.modelName) {
+        setModelStatus('请选择 OLLama 平台并填写模型名称');
+        return;
+    }
+
+    const unlisten = await listen('model-pull-progress', (event) => {
+        const { status, total, completed } = event.payload;
+        if (total && completed !== null && completed !== undefined) {
+            setModelStatus(`${status} ${Math.floor(completed * 100 / total)}%`);
+        } else {
+            setModelStatus(status);
+        }
+    });
+    try {
+        setModelStatus('开始拉取模型...');
+        await invoke('pull_model', { profile, name: profile.modelName });
+        setModelStatus(`模型 ${profile.modelName} 拉取完成`);
+        await loadModelOptions();
+    } catch (error) {
+        console.error('拉取模型失败:', error);
+        setModelStatus(error.msg || '拉取模型失败');
+    } finally {
+        unlisten();
+    }
+}
+
+// 加载已导入的离线词典
+async function loadDictionaries() {
+    const list = document.getElementById('dictionaryList');
+    if (!list) {
+        return;
+    }
+    try {
+        const result = await invoke('list_dictionaries');
+        list.innerHTML = '';
+        if (result && result.code === 0) {
+            result.data.forEach(dictionary => {
+                const item = document.createElement('div');
+                item.className = 'dictionary-item';
+                const name = document.createElement('span');
+                name.textContent = `${dictionary.name}（${dictionary.word_count} 词）`;
+                name.title = dictionary.source;
+                const removeBtn = document.createElement('button');
+                removeBtn.type = 'button';
+                removeBtn.className = 'dictionary-remove';
+                removeBtn.textContent = '✕';
+                removeBtn.title = '删除词典';
+                removeBtn.addEventListener('click', () => removeDictionary(dictionary));
+                item.append(name, removeBtn);
+                list.appendChild(item);
+            });
+        }
+    } catch (error) {
+        console.error('获取词典列表失败:', error);
+    }
+}
+
+// 导入离线词典
+async function importDictionary() {
+    const input = document.getElementById('dictionaryPath');
+    const path = input.value.trim();
+    if (!path) {
+        alert('请填写词典文件路径');
+        return;
+    }
+    try {
+        const result = await invoke('import_dictionary', { path });
+        if (result.code === 0) {
+            input.value = '';
+            await loadDictionaries();
+        } else {
+            alert('导入词典失败:' + result.msg);
+        }
+    } catch (error) {
+        console.error('导入词典失败:', error);
+        alert(error);
+    }
+}
+
+// 删除离线词典
+async function removeDictionary(dictionary) {
+    if (!confirm(`确定删除词典「${dictionary.name}」吗？`)) {
+        return;
+    }
+    try {
+        await invoke('remove_dictionary', { id: dictionary.id });
+        await loadDictionaries();
+    } catch (error) {
+        console.error('删除词典失败:', error);
     }
 }
 
@@ -174,4 +345,4 @@ function togglePasswordVisibility() {
     }
 }
 
-export { resetSettings, saveSettings, togglePasswordVisibility, toggleSettingsPage };
+export { importDictionary, loadModelOptions, pullModel, resetSettings, saveSettings, showSettingsPage, togglePasswordVisibility, toggleSettingsPage };
@@ -19,6 +19,17 @@ const placeholders = {
 // 翻译状态
 let isTranslating = false;
 let translateTimeout = null;
+// 当前译文对应的翻译记录 ID，为空时没有可以调整的译文
+let refineRecordId = null;
+
+// 与后端的词典模式判断一致：单行，最多三个单词，中日韩文字最多 4 个字
+function isWordOrPhrase(text) {
+    const cjkChars = text.match(/[\u1100-\u11ff\u3040-\u30ff\u3130-\u318f\u3400-\u4dbf\u4e00-\u9fff\uac00-\ud7af\uf900-\ufaff]/g) || [];
+    return !text.includes('\n')
+        && [...text].length <= 32
+        && text.split(/\s+/).length <= 3
+        && cjkChars.length <= 4;
+}
 
 // 语言检测
 function detectLanguage(text) {
@@ -37,23 +48,29 @@ async function translateText() {
     const target = window.targetLang ? window.targetLang.value : 'en';
     
     if (source === target) {
+        refineRecordId = null;
         window.translatedText.value = text;
         return;
     }
     
     try {
+        refineRecordId = null;
         isTranslating = true;
         window.translatedText.value = '翻译中...';
         window.translatedText.style.opacity = '0.6';
         
-        const result = await invoke('translate', {
+        // 单词和短语会返回词典释义，其他文本按普通翻译处理
+        const useDictionary = isWordOrPhrase(text);
+        const result = await invoke(useDictionary ? 'translate_dictionary' : 'translate', {
             text: text,
             targetLang: target,
             sourceLang: source
         });
         
         if (result.code === 0 && result.data) {
-            window.translatedText.value = result.data;
+            // 使用离线词典的释义时没有翻译记录，不能调整
+            refineRecordId = result.data.id || null;
+            window.translatedText.value = useDictionary ? formatDictionaryResult(result.data) : result.data.text;
             window.translatedText.style.opacity = '1';
             autoResizeTextarea(window.translatedText);
         } else {
@@ -71,6 +88,90 @@ async function translateText() {
     }
 }
 
+// 显示后台翻译的结果，点击系统通知时调用，不重新翻译
+function showTranslationResult(result) {
+    refineRecordId = result.id;
+    window.sourceText.value = result.source_text;
+    window.translatedText.value = result.translated_text;
+    window.translatedText.style.opacity = '1';
+    autoResizeTextarea(window.sourceText);
+    autoResizeTextarea(window.translatedText);
+}
+
+// 将词典释义格式化为多行文本
+function formatDictionaryResult(result) {
+    if (result.definitions && result.definitions.length > 0) {
+        return formatOfflineDefinitions(result.definitions);
+    }
+    const entry = result.entry;
+    if (!entry) {
+        return result.translation;
+    }
+
+    const lines = [result.translation];
+    if (entry.pronunciation) {
+        lines.push(entry.pronunciation);
+    }
+    lines.push('');
+    entry.senses.forEach(sense => {
+        const pos = sense.partOfSpeech ? `[${sense.partOfSpeech}] ` : '';
+        lines.push(`${pos}${sense.translations.join('；')}`);
+        if (sense.explanation) {
+            lines.push(`  ${sense.explanation}`);
+        }
+    });
+    if (entry.examples.length > 0) {
+        lines.push('');
+        entry.examples.forEach(example => {
+            lines.push(`• ${example.source}`);
+            lines.push(`  ${example.translation}`);
+        });
+    }
+    return lines.join('\n');
+}
+
+// 将离线词典释义格式化为多行文本，多个词典之间空一行
+function formatOfflineDefinitions(definitions) {
+    return definitions.map(definition => {
+        const lines = [definition.word];
+        if (definition.phonetic) {
+            lines.push(definition.phonetic);
+        }
+        lines.push(definition.definition);
+        lines.push(`[${definition.dictionary}]`);
+        return lines.join('\n');
+    }).join('\n\n');
+}
+
+// 按调整要求修改当前译文
+async function refineTranslation(instruction) {
+    if (!instruction || isTranslating || !window.translatedText.value.trim()) return;
+    if (!refineRecordId) {
+        alert('当前译文不能调整');
+        return;
+    }
+
+    try {
+        isTranslating = true;
+        window.translatedText.style.opacity = '0.6';
+        const result = await invoke('refine_translation', {
+            recordId: refineRecordId,
+            instruction
+        });
+        if (result.code === 0 && result.data) {
+            refineRecordId = result.data.id;
+            window.translatedText.value = result.data.translated_text;
+            autoResizeTextarea(window.translatedText);
+        }
+    } catch (error) {
+        console.error('Refine error:', error);
+        alert(error.msg || '调整译文失败');
+    } finally {
+        window.translatedText.style.opacity = '1';
+        isTranslating = false;
+    }
+}
+
 // 处理文本输入
 function handleTextInput() {
     if (translateTimeout) {
@@ -89,4 +190,4 @@ function handleTextInput() {
     }, 500);
 }
 
-export { translateText, handleTextInput, languages, placeholders, detectLanguage };
\ No newline at end of file
+export { translateText, handleTextInput, refineTranslation, showTranslationResult, languages, placeholders, detectLanguage };
\ No newline at end of file